@@ -4,12 +4,22 @@
 //! This module is temporary, and the extension traits defined here are expected to be
 //! upstreamed into the `ff` and `group` crates after some refactoring.
 
+mod bn;
 mod curves;
 mod fields;
+#[cfg(feature = "alloc")]
+mod msm;
 mod pairing;
 
+#[cfg(feature = "alloc")]
+pub(crate) use bn::affine_miller_loop;
+pub use bn::*;
 pub use curves::*;
 pub use fields::*;
+#[cfg(feature = "alloc")]
+pub use msm::*;
+#[cfg(feature = "alloc")]
+pub(crate) use msm::{get_at, window_size};
 pub use pairing::*;
 
 /// This represents an element of a group with basic operations that can be
@@ -0,0 +1,784 @@
+//! This module provides the optimal ate pairing over Barreto-Naehrig curves,
+//! generic over the parameters of the curve given by [`BnParameters`].
+
+use super::CurveAffine;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt::Debug;
+use core::ops::MulAssign;
+use ff::Field;
+use subtle::CtOption;
+
+/// The type of the sextic twist $E'$ of a BN curve $E: y^2 = x^3 + b$ on
+/// which G2 is defined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TwistType {
+    /// $E': y^2 = x^3 + b / \xi$, whose lines are sparse with non-zero
+    /// coefficients at $1$, $w$ and $vw$.
+    D,
+    /// $E': y^2 = x^3 + b \xi$, whose lines are sparse with non-zero
+    /// coefficients at $1$, $v$ and $vw$.
+    M,
+}
+
+/// The arithmetic the pairing needs in $\mathbb{F}_{q^2}$.
+pub trait BnFq2<Fq>: Field {
+    /// Raises this element to the power $q$.
+    fn conjugate(&mut self);
+
+    /// Multiplies this element by an element of $\mathbb{F}_q$.
+    fn mul_by_base(&mut self, c: &Fq);
+}
+
+/// The arithmetic the pairing needs in $\mathbb{F}_{q^{12}}$, built by
+/// adjoining $w$ with $w^2 = v$ to $\mathbb{F}_{q^6}$, itself built by
+/// adjoining $v$ with $v^3 = \xi$ to $\mathbb{F}_{q^2}$.
+pub trait BnFq12<Fq2>: Field {
+    /// Multiplies this element by $c_0 + c_3 w + c_4 v w$.
+    fn mul_by_034(&mut self, c0: &Fq2, c3: &Fq2, c4: &Fq2);
+
+    /// Multiplies this element by $c_0 + c_1 v + c_4 v w$.
+    fn mul_by_014(&mut self, c0: &Fq2, c1: &Fq2, c4: &Fq2);
+
+    /// Raises this element to the power $q^6$, which is the inverse in the
+    /// cyclotomic subgroup.
+    fn conjugate(&mut self);
+
+    /// Raises this element to the power $q^{power}$.
+    fn frobenius_map(&mut self, power: usize);
+
+    /// Squares this element of the cyclotomic subgroup.
+    fn cyclotomic_square(&mut self);
+
+    /// Raises this element of the cyclotomic subgroup to the power of the
+    /// little endian limbs `exp`, for instance with Karabina's compressed
+    /// squarings. This may take time that depends on `exp`, which is public
+    /// when the pairing calls it, but not on this element.
+    fn cyclotomic_exp_vartime(&self, exp: &[u64]) -> Self;
+}
+
+/// An affine point of G1 or G2 as the pairing reads it.
+pub trait BnAffine<F>: Copy + Debug {
+    /// Returns the coordinates of this point, or `None` if it is the
+    /// identity.
+    fn xy(&self) -> Option<(F, F)>;
+}
+
+impl<C: CurveAffine> BnAffine<C::Base> for C {
+    fn xy(&self) -> Option<(C::Base, C::Base)> {
+        if bool::from(self.is_identity()) {
+            return None;
+        }
+        let c = self.coordinates().unwrap();
+        Some((*c.x(), *c.y()))
+    }
+}
+
+/// The parameters of a BN curve $E: y^2 = x^3 + b$ over $\mathbb{F}_q$ with
+/// $q = 36u^4 + 36u^3 + 24u^2 + 6u + 1$, and of its sextic twist over
+/// $\mathbb{F}_{q^2}$.
+///
+/// The non-residue $\xi$ is not a parameter: the field types fix it, and the
+/// pairing only sees it through `TWIST_TYPE` and the Frobenius coefficients.
+pub trait BnParameters: 'static {
+    type Fq: Field;
+    type Fq2: BnFq2<Self::Fq>;
+    type Fq12: BnFq12<Self::Fq2>;
+    type G1Affine: BnAffine<Self::Fq>;
+    type G2Affine: BnAffine<Self::Fq2>;
+
+    /// The absolute value of $u$ as little endian limbs.
+    const X: &'static [u64];
+
+    /// Whether $u$ is negative.
+    const X_IS_NEGATIVE: bool;
+
+    /// The NAF of $|6u + 2|$, least significant digit first.
+    const ATE_LOOP_COUNT: &'static [i8];
+
+    /// The type of the twist on which G2 is defined, relative to the
+    /// non-residue $\xi$ of $\mathbb{F}_{q^2}$ that builds the tower.
+    const TWIST_TYPE: TwistType;
+
+    /// The coefficient of $\bar{x}$ in the Frobenius endomorphism of $E$
+    /// carried to the twist, which is $\xi^{(q - 1) / 3}$ for a D-type twist
+    /// and $\xi^{-(q - 1) / 3}$ for an M-type twist.
+    const TWIST_MUL_BY_Q_X: Self::Fq2;
+
+    /// The coefficient of $\bar{y}$ in the Frobenius endomorphism of $E$
+    /// carried to the twist, which is $\xi^{(q - 1) / 2}$ for a D-type twist
+    /// and $\xi^{-(q - 1) / 2}$ for an M-type twist.
+    const TWIST_MUL_BY_Q_Y: Self::Fq2;
+
+    /// The coefficient of $x$ in the square of the Frobenius endomorphism
+    /// carried to the twist. The coefficient of $y$ is always $-1$.
+    const TWIST_MUL_BY_Q2_X: Self::Fq2;
+}
+
+/// Returns the image of the affine point `q` of the twist under the Frobenius
+/// endomorphism.
+#[cfg(feature = "alloc")]
+fn mul_by_char<P: BnParameters>(q: &(P::Fq2, P::Fq2)) -> (P::Fq2, P::Fq2) {
+    let (mut x, mut y) = *q;
+
+    x.conjugate();
+    x.mul_assign(&P::TWIST_MUL_BY_Q_X);
+
+    y.conjugate();
+    y.mul_assign(&P::TWIST_MUL_BY_Q_Y);
+
+    (x, y)
+}
+
+/// Returns the negation of the image of the affine point `q` of the twist
+/// under the square of the Frobenius endomorphism.
+#[cfg(feature = "alloc")]
+fn neg_mul_by_char2<P: BnParameters>(q: &(P::Fq2, P::Fq2)) -> (P::Fq2, P::Fq2) {
+    let (mut x, y) = *q;
+
+    x.mul_assign(&P::TWIST_MUL_BY_Q2_X);
+
+    (x, y)
+}
+
+/// Multiplies `f` by the line of prepared coefficients `(a, b, c)` evaluated
+/// at the affine G1 point `p`, which is $a y_P + b x_P w + c v w$ for a D-type
+/// twist and $c + b x_P v + a y_P v w$ for an M-type twist.
+#[cfg(feature = "alloc")]
+fn mul_by_line<P: BnParameters>(
+    f: &mut P::Fq12,
+    (a, b, c): &(P::Fq2, P::Fq2, P::Fq2),
+    p: &(P::Fq, P::Fq),
+) {
+    let mut a = *a;
+    let mut b = *b;
+
+    a.mul_by_base(&p.1);
+    b.mul_by_base(&p.0);
+
+    match P::TWIST_TYPE {
+        TwistType::D => f.mul_by_034(&a, &b, c),
+        TwistType::M => f.mul_by_014(c, &b, &a),
+    }
+}
+
+/// A G2 point in Jacobian coordinates, used as the running multiple of the
+/// point being prepared.
+#[cfg(feature = "alloc")]
+struct G2Jacobian<F> {
+    x: F,
+    y: F,
+    z: F,
+}
+
+/// A G2 point with the line coefficients of the Miller loop precomputed, see
+/// [`bn_multi_miller_loop`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct BnG2Prepared<P: BnParameters> {
+    pub(crate) coeffs: Vec<(P::Fq2, P::Fq2, P::Fq2)>,
+    pub(crate) infinity: bool,
+    pub(crate) point: P::G2Affine,
+}
+
+#[cfg(feature = "alloc")]
+impl<P: BnParameters> BnG2Prepared<P> {
+    pub fn is_zero(&self) -> bool {
+        self.infinity
+    }
+
+    /// Returns the point this was prepared from.
+    pub fn point(&self) -> P::G2Affine {
+        self.point
+    }
+
+    pub fn from_affine(q: P::G2Affine) -> Self {
+        let q_xy = match q.xy() {
+            Some(q_xy) => q_xy,
+            None => {
+                return BnG2Prepared {
+                    coeffs: vec![],
+                    infinity: true,
+                    point: q,
+                }
+            }
+        };
+
+        fn doubling_step<F: Field>(r: &mut G2Jacobian<F>) -> (F, F, F) {
+            // Adaptation of Algorithm 26, https://eprint.iacr.org/2010/354.pdf
+            let mut tmp0 = r.x.square();
+
+            let mut tmp1 = r.y.square();
+
+            let mut tmp2 = tmp1.square();
+
+            let mut tmp3 = tmp1;
+            tmp3 += &r.x;
+            tmp3 = tmp3.square();
+            tmp3 -= &tmp0;
+            tmp3 -= &tmp2;
+            tmp3 = tmp3.double();
+
+            let mut tmp4 = tmp0.double();
+            tmp4 += &tmp0;
+
+            let mut tmp6 = r.x;
+            tmp6 += &tmp4;
+
+            let tmp5 = tmp4.square();
+
+            let zsquared = r.z.square();
+
+            r.x = tmp5;
+            r.x -= &tmp3;
+            r.x -= &tmp3;
+
+            r.z += &r.y;
+            r.z = r.z.square();
+            r.z -= &tmp1;
+            r.z -= &zsquared;
+
+            r.y = tmp3;
+            r.y -= &r.x;
+            r.y.mul_assign(&tmp4);
+
+            tmp2 = tmp2.double().double().double();
+
+            r.y -= &tmp2;
+
+            // up to here everything was by algorith, line 11
+            // use R instead of new T
+
+            // tmp3 is the first part of line 12
+            tmp3 = tmp4;
+            tmp3.mul_assign(&zsquared);
+            tmp3 = tmp3.double();
+            tmp3 = tmp3.neg();
+
+            // tmp6 is from line 14
+            tmp6 = tmp6.square();
+            tmp6 -= &tmp0;
+            tmp6 -= &tmp5;
+
+            tmp1 = tmp1.double().double();
+
+            tmp6 -= &tmp1;
+
+            // tmp0 is the first part of line 16
+            tmp0 = r.z;
+            tmp0.mul_assign(&zsquared);
+            tmp0 = tmp0.double();
+
+            (tmp0, tmp3, tmp6)
+        }
+
+        fn addition_step<F: Field>(r: &mut G2Jacobian<F>, q: &(F, F)) -> (F, F, F) {
+            // Adaptation of Algorithm 27, https://eprint.iacr.org/2010/354.pdf
+            let zsquared = r.z.square();
+
+            let ysquared = q.1.square();
+
+            // t0 corresponds to line 1
+            let mut t0 = zsquared;
+            t0.mul_assign(&q.0);
+
+            // t1 corresponds to lines 2 and 3
+            let mut t1 = q.1;
+            t1 += &r.z;
+            t1 = t1.square();
+            t1 -= &ysquared;
+            t1 -= &zsquared;
+            t1.mul_assign(&zsquared);
+
+            // t2 corresponds to line 4
+            let mut t2 = t0;
+            t2 -= &r.x;
+
+            // t3 corresponds to line 5
+            let t3 = t2.square();
+
+            // t4 corresponds to line 6
+            let t4 = t3.double().double();
+
+            // t5 corresponds to line 7
+            let mut t5 = t4;
+            t5.mul_assign(&t2);
+
+            // t6 corresponds to line 8
+            let mut t6 = t1;
+            t6 -= &r.y;
+            t6 -= &r.y;
+
+            // t9 corresponds to line 9
+            let mut t9 = t6;
+            t9.mul_assign(&q.0);
+
+            // corresponds to line 10
+            let mut t7 = t4;
+            t7.mul_assign(&r.x);
+
+            // corresponds to line 11, but assigns to r.x instead of T.x
+            r.x = t6.square();
+            r.x -= &t5;
+            r.x -= &t7;
+            r.x -= &t7;
+
+            // corresponds to line 12, but assigns to r.z instead of T.z
+            r.z += &t2;
+            r.z = r.z.square();
+            r.z -= &zsquared;
+            r.z -= &t3;
+
+            // corresponds to line 13
+            let mut t10 = q.1;
+            t10 += &r.z;
+
+            // corresponds to line 14
+            let mut t8 = t7;
+            t8 -= &r.x;
+            t8.mul_assign(&t6);
+
+            // corresponds to line 15
+            t0 = r.y;
+            t0.mul_assign(&t5);
+            t0 = t0.double();
+
+            // corresponds to line 12, but assigns to r.y instead of T.y
+            r.y = t8;
+            r.y -= &t0;
+
+            // corresponds to line 17
+            t10 = t10.square();
+            t10 -= &ysquared;
+
+            let ztsquared = r.z.square();
+
+            t10 -= &ztsquared;
+
+            // corresponds to line 18
+            t9 = t9.double();
+            t9 -= &t10;
+
+            // t10 = 2*Zt from Algo 27, line 19
+            t10 = r.z.double();
+
+            // t1 = first multiplicator of line 21
+            t6 = t6.neg();
+
+            t1 = t6.double();
+
+            // t9 corresponds to t9 from Algo 27
+            (t10, t1, t9)
+        }
+
+        let mut coeffs = vec![];
+        let mut r = G2Jacobian {
+            x: q_xy.0,
+            y: q_xy.1,
+            z: P::Fq2::one(),
+        };
+
+        let negq = (q_xy.0, -q_xy.1);
+
+        for i in (1..P::ATE_LOOP_COUNT.len()).rev() {
+            coeffs.push(doubling_step(&mut r));
+            let x = P::ATE_LOOP_COUNT[i - 1];
+            match x {
+                1 => {
+                    coeffs.push(addition_step(&mut r, &q_xy));
+                }
+                -1 => {
+                    coeffs.push(addition_step(&mut r, &negq));
+                }
+                _ => continue,
+            }
+        }
+
+        if P::X_IS_NEGATIVE {
+            r.y = -r.y;
+        }
+
+        coeffs.push(addition_step(&mut r, &mul_by_char::<P>(&q_xy)));
+        coeffs.push(addition_step(&mut r, &neg_mul_by_char2::<P>(&q_xy)));
+
+        BnG2Prepared {
+            coeffs,
+            infinity: false,
+            point: q,
+        }
+    }
+}
+
+/// Computes $\prod_i f_{6u + 2, Q_i}(P_i)$ times the lines of the two
+/// Frobenius addition steps of the optimal ate pairing, over the `terms`
+/// $(P_i, Q_i)$.
+#[cfg(feature = "alloc")]
+pub fn bn_multi_miller_loop<P: BnParameters>(
+    terms: &[(&P::G1Affine, &BnG2Prepared<P>)],
+) -> P::Fq12 {
+    let mut pairs = vec![];
+    for &(p, q) in terms {
+        if let (Some(p), false) = (p.xy(), q.is_zero()) {
+            pairs.push((p, q.coeffs.iter()));
+        }
+    }
+
+    let mut f = P::Fq12::one();
+
+    for i in (1..P::ATE_LOOP_COUNT.len()).rev() {
+        if i != P::ATE_LOOP_COUNT.len() - 1 {
+            f = f.square();
+        }
+        for (p, coeffs) in &mut pairs {
+            mul_by_line::<P>(&mut f, coeffs.next().unwrap(), p);
+        }
+        if P::ATE_LOOP_COUNT[i - 1] != 0 {
+            for (p, coeffs) in &mut pairs {
+                mul_by_line::<P>(&mut f, coeffs.next().unwrap(), p);
+            }
+        }
+    }
+
+    if P::X_IS_NEGATIVE {
+        f.conjugate();
+    }
+
+    for (p, coeffs) in &mut pairs {
+        mul_by_line::<P>(&mut f, coeffs.next().unwrap(), p);
+    }
+
+    for (p, coeffs) in &mut pairs {
+        mul_by_line::<P>(&mut f, coeffs.next().unwrap(), p);
+    }
+
+    for (_p, coeffs) in &mut pairs {
+        assert_eq!(coeffs.next(), None);
+    }
+
+    f
+}
+
+/// Computes the same Miller loop as [`bn_multi_miller_loop`], up to a factor
+/// that vanishes in the final exponentiation, with the lines evaluated on the
+/// fly from affine multiples of the unprepared $Q_i$.
+#[cfg(feature = "alloc")]
+pub fn bn_multi_miller_loop_affine<P: BnParameters>(
+    terms: &[(&P::G1Affine, &P::G2Affine)],
+) -> P::Fq12 {
+    let pairs = terms
+        .iter()
+        .filter(|(_, q)| q.xy().is_some())
+        .filter_map(|&(p, q)| {
+            let (x, y) = p.xy()?;
+            let mut y_p = P::Fq2::one();
+            y_p.mul_by_base(&y);
+            Some(((x, y_p), q))
+        });
+
+    // The line through T of slope lambda is y_P - lambda * x_P * w + c * v * w
+    // after untwisting a D-type twist, where c = lambda * x_T - y_T, and
+    // w^3 (c - lambda * x_P * w^-1 + y_P * w^-3) for an M-type twist.
+    affine_miller_loop::<P, _, _, _>(pairs, |f, (x, y), lambda, c| {
+        let mut lambda_x = *lambda;
+        lambda_x.mul_by_base(x);
+        let lambda_x = -lambda_x;
+
+        match P::TWIST_TYPE {
+            TwistType::D => f.mul_by_034(y, &lambda_x, c),
+            TwistType::M => f.mul_by_014(c, &lambda_x, y),
+        }
+    })
+}
+
+/// Runs the Miller loop over the `pairs` (P, Q) with the multiples T of Q in
+/// affine coordinates. `mul_by_line` multiplies `f` by the line through T of
+/// slope `lambda` evaluated at P, given `lambda` and `lambda * x_T - y_T`.
+#[cfg(feature = "alloc")]
+pub(crate) fn affine_miller_loop<'a, P, T, I, L>(pairs: I, mul_by_line: L) -> P::Fq12
+where
+    P: BnParameters,
+    I: Iterator<Item = (T, &'a P::G2Affine)>,
+    L: Fn(&mut P::Fq12, &T, &P::Fq2, &P::Fq2),
+{
+    use ff::BatchInvert;
+
+    // (P, Q, T) where T is the running multiple of Q
+    let mut pairs: Vec<_> = pairs
+        .map(|(p, q)| {
+            let q = q.xy().unwrap();
+            (p, q, q)
+        })
+        .collect();
+    let mut inverses = vec![P::Fq2::zero(); pairs.len()];
+
+    // Multiplies f by the line through T of slope lambda evaluated at P, and
+    // replaces T by the sum of T and the point of abscissa x, the other point
+    // of the line on the curve.
+    fn ell<P: BnParameters, T>(
+        f: &mut P::Fq12,
+        t: &mut (P::Fq2, P::Fq2),
+        lambda: &P::Fq2,
+        x: &P::Fq2,
+        p: &T,
+        mul_by_line: &impl Fn(&mut P::Fq12, &T, &P::Fq2, &P::Fq2),
+    ) {
+        mul_by_line(f, p, lambda, &(*lambda * t.0 - t.1));
+
+        let x3 = lambda.square() - t.0 - x;
+        t.1 = *lambda * (t.0 - x3) - t.1;
+        t.0 = x3;
+    }
+
+    type Pair<P, T> = (
+        T,
+        (<P as BnParameters>::Fq2, <P as BnParameters>::Fq2),
+        (<P as BnParameters>::Fq2, <P as BnParameters>::Fq2),
+    );
+
+    fn doubling_step<P: BnParameters, T>(
+        f: &mut P::Fq12,
+        pairs: &mut [Pair<P, T>],
+        inverses: &mut [P::Fq2],
+        mul_by_line: &impl Fn(&mut P::Fq12, &T, &P::Fq2, &P::Fq2),
+    ) {
+        for (inverse, (_, _, t)) in inverses.iter_mut().zip(pairs.iter()) {
+            *inverse = t.1.double();
+        }
+        inverses.iter_mut().batch_invert();
+
+        for (inverse, (p, _, t)) in inverses.iter().zip(pairs.iter_mut()) {
+            // lambda = 3 x_T^2 / (2 y_T)
+            let x_squared = t.0.square();
+            let lambda = (x_squared.double() + x_squared) * inverse;
+            let x = t.0;
+            ell::<P, T>(f, t, &lambda, &x, p, mul_by_line);
+        }
+    }
+
+    // Adds to every T the image of its Q under `map`.
+    fn addition_step<P: BnParameters, T>(
+        f: &mut P::Fq12,
+        pairs: &mut [Pair<P, T>],
+        inverses: &mut [P::Fq2],
+        mul_by_line: &impl Fn(&mut P::Fq12, &T, &P::Fq2, &P::Fq2),
+        map: impl Fn(&(P::Fq2, P::Fq2)) -> (P::Fq2, P::Fq2),
+    ) {
+        for (inverse, (_, q, t)) in inverses.iter_mut().zip(pairs.iter()) {
+            *inverse = map(q).0 - t.0;
+        }
+        inverses.iter_mut().batch_invert();
+
+        for (inverse, (p, q, t)) in inverses.iter().zip(pairs.iter_mut()) {
+            // lambda = (y_Q - y_T) / (x_Q - x_T)
+            let q = map(q);
+            let lambda = (q.1 - t.1) * inverse;
+            ell::<P, T>(f, t, &lambda, &q.0, p, mul_by_line);
+        }
+    }
+
+    let line = &mul_by_line;
+    let mut f = P::Fq12::one();
+
+    for i in (1..P::ATE_LOOP_COUNT.len()).rev() {
+        if i != P::ATE_LOOP_COUNT.len() - 1 {
+            f = f.square();
+        }
+        doubling_step::<P, T>(&mut f, &mut pairs, &mut inverses, line);
+        match P::ATE_LOOP_COUNT[i - 1] {
+            1 => addition_step::<P, T>(&mut f, &mut pairs, &mut inverses, line, |q| *q),
+            -1 => addition_step::<P, T>(&mut f, &mut pairs, &mut inverses, line, |q| (q.0, -q.1)),
+            _ => continue,
+        }
+    }
+
+    if P::X_IS_NEGATIVE {
+        f.conjugate();
+        for (_, _, t) in pairs.iter_mut() {
+            t.1 = -t.1;
+        }
+    }
+
+    addition_step::<P, T>(&mut f, &mut pairs, &mut inverses, line, mul_by_char::<P>);
+    addition_step::<P, T>(
+        &mut f,
+        &mut pairs,
+        &mut inverses,
+        line,
+        neg_mul_by_char2::<P>,
+    );
+
+    f
+}
+
+/// Raises the result `f` of a Miller loop to the power $(q^{12} - 1) / r$,
+/// failing if `f` is zero.
+pub fn bn_final_exponentiation<P: BnParameters>(f: &P::Fq12) -> CtOption<P::Fq12> {
+    // Only branches on the bits of the public x, not on f.
+    fn exp_by_x<P: BnParameters>(f: &mut P::Fq12) {
+        *f = f.cyclotomic_exp_vartime(P::X);
+        if P::X_IS_NEGATIVE {
+            f.conjugate();
+        }
+    }
+
+    let r = *f;
+    let mut f1 = *f;
+    f1.conjugate();
+
+    r.invert().map(|mut f2| {
+        let mut r = f1;
+        r.mul_assign(&f2);
+        f2 = r;
+        r.frobenius_map(2);
+        r.mul_assign(&f2);
+
+        let mut fp = r;
+        fp.frobenius_map(1);
+
+        let mut fp2 = r;
+        fp2.frobenius_map(2);
+        let mut fp3 = fp2;
+        fp3.frobenius_map(1);
+
+        let mut fu = r;
+        exp_by_x::<P>(&mut fu);
+
+        let mut fu2 = fu;
+        exp_by_x::<P>(&mut fu2);
+
+        let mut fu3 = fu2;
+        exp_by_x::<P>(&mut fu3);
+
+        let mut y3 = fu;
+        y3.frobenius_map(1);
+
+        let mut fu2p = fu2;
+        fu2p.frobenius_map(1);
+
+        let mut fu3p = fu3;
+        fu3p.frobenius_map(1);
+
+        let mut y2 = fu2;
+        y2.frobenius_map(2);
+
+        let mut y0 = fp;
+        y0.mul_assign(&fp2);
+        y0.mul_assign(&fp3);
+
+        let mut y1 = r;
+        y1.conjugate();
+
+        let mut y5 = fu2;
+        y5.conjugate();
+
+        y3.conjugate();
+
+        let mut y4 = fu;
+        y4.mul_assign(&fu2p);
+        y4.conjugate();
+
+        let mut y6 = fu3;
+        y6.mul_assign(&fu3p);
+        y6.conjugate();
+
+        y6.cyclotomic_square();
+        y6.mul_assign(&y4);
+        y6.mul_assign(&y5);
+
+        let mut t1 = y3;
+        t1.mul_assign(&y5);
+        t1.mul_assign(&y6);
+
+        y6.mul_assign(&y2);
+
+        t1.cyclotomic_square();
+        t1.mul_assign(&y6);
+        t1.cyclotomic_square();
+
+        let mut t0 = t1;
+        t0.mul_assign(&y1);
+
+        t1.mul_assign(&y0);
+
+        t0.cyclotomic_square();
+        t0.mul_assign(&t1);
+
+        t0
+    })
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::tests::bn::*;
+    use rand::{RngCore, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    fn pairing(p: &Point<Fp>, q: &Point<Fp2>) -> Fp12 {
+        let q = BnG2Prepared::<ToyBn>::from_affine(*q);
+        bn_final_exponentiation::<ToyBn>(&bn_multi_miller_loop::<ToyBn>(&[(p, &q)])).unwrap()
+    }
+
+    #[test]
+    fn test_toy_parameters() {
+        // The NAF digits add up to |6u + 2| with u = -4619.
+        let naf = ToyBn::ATE_LOOP_COUNT
+            .iter()
+            .rev()
+            .fold(0i64, |acc, &digit| 2 * acc + digit as i64);
+        assert_eq!(-naf, 6 * -(ToyBn::X[0] as i64) + 2);
+
+        let xi_inv = XI.invert().unwrap();
+        let q_x = xi_inv.pow_vartime([(Q - 1) / 3]);
+        assert_eq!(ToyBn::TWIST_MUL_BY_Q_X, q_x);
+        assert_eq!(ToyBn::TWIST_MUL_BY_Q_Y, xi_inv.pow_vartime([(Q - 1) / 2]));
+        let mut q2_x = q_x;
+        q2_x.conjugate();
+        assert_eq!(ToyBn::TWIST_MUL_BY_Q2_X, q2_x * q_x);
+
+        let b = Fp2(B, Fp::zero());
+        assert!(G1_GENERATOR.is_on_curve(&B));
+        assert!(G2_GENERATOR.is_on_curve(&(b * XI)));
+        assert_eq!(G1_GENERATOR.mul(R), Point(None));
+        assert_eq!(G2_GENERATOR.mul(R), Point(None));
+    }
+
+    // Runs the M-type twist and negative u branches, which BN254 does not.
+    #[test]
+    fn test_toy_pairing() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let g = pairing(&G1_GENERATOR, &G2_GENERATOR);
+        assert_ne!(g, Fp12::one());
+        assert_eq!(g.pow_vartime([R]), Fp12::one());
+
+        for _ in 0..5 {
+            let a = rng.next_u64() % R;
+            let b = rng.next_u64() % R;
+            let p = G1_GENERATOR.mul(a);
+            let q = G2_GENERATOR.mul(b);
+            let ab = (a as u128 * b as u128 % R as u128) as u64;
+
+            let e = pairing(&p, &q);
+            assert_eq!(e, g.pow_vartime([ab]));
+            assert_eq!(e, pairing(&G1_GENERATOR.mul(ab), &G2_GENERATOR));
+
+            let affine = bn_multi_miller_loop_affine::<ToyBn>(&[(&p, &q)]);
+            assert_eq!(bn_final_exponentiation::<ToyBn>(&affine).unwrap(), e);
+
+            // e(P, Q) * e(-P, Q) = 1, with an identity term that is skipped
+            let neg_p = Point(p.0.map(|(x, y)| (x, -y)));
+            let q_prepared = BnG2Prepared::<ToyBn>::from_affine(q);
+            let identity = BnG2Prepared::<ToyBn>::from_affine(Point(None));
+            let terms = [(&p, &q_prepared), (&neg_p, &q_prepared), (&p, &identity)];
+            let f = bn_multi_miller_loop::<ToyBn>(&terms);
+            assert_eq!(bn_final_exponentiation::<ToyBn>(&f).unwrap(), Fp12::one());
+            let f =
+                bn_multi_miller_loop_affine::<ToyBn>(&[(&p, &q), (&neg_p, &q), (&Point(None), &q)]);
+            assert_eq!(bn_final_exponentiation::<ToyBn>(&f).unwrap(), Fp12::one());
+        }
+    }
+}
@@ -32,7 +32,7 @@ pub trait CurveExt:
 
     /// Apply the curve endomorphism by multiplying the x-coordinate
     /// by an element of multiplicative order 3.
-    // fn endo(&self) -> Self;
+    fn endo(&self) -> Self;
 
     /// Return the Jacobian coordinates of this point.
     fn jacobian_coordinates(&self) -> (Self::Base, Self::Base, Self::Base);
@@ -6,6 +6,7 @@ use subtle::{Choice, ConstantTimeEq};
 
 use super::Group;
 
+#[cfg(feature = "std")]
 use std::io::{self, Read, Write};
 
 const_assert!(size_of::<usize>() >= 4);
@@ -16,11 +17,6 @@ pub trait BaseExt: ff::Field + Ord + ConstantTimeEq {
     /// Modulus of the field written as a string for display purposes
     const MODULUS: &'static str;
 
-    /// This computes a random element of the field using system randomness.
-    fn rand() -> Self {
-        Self::random(rand::rngs::OsRng)
-    }
-
     /// Returns whether or not this element is zero.
     fn ct_is_zero(&self) -> Choice {
         self.ct_eq(&Self::zero())
@@ -30,13 +26,6 @@ pub trait BaseExt: ff::Field + Ord + ConstantTimeEq {
     /// byte representation of an integer.
     fn from_bytes_wide(bytes: &[u8; 64]) -> Self;
 
-    /// Writes this element in its normalized, little endian form into a buffer.
-    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()>;
-
-    /// Reads a normalized, little endian represented field element from a
-    /// buffer.
-    fn read<R: Read>(reader: &mut R) -> io::Result<Self>;
-
     /// Exponentiates `self` by `by`, where `by` is a little-endian order
     /// integer exponent.
     fn pow(&self, by: &[u64; 4]) -> Self {
@@ -53,6 +42,24 @@ pub trait BaseExt: ff::Field + Ord + ConstantTimeEq {
     }
 }
 
+/// The part of [`BaseExt`] that needs `std`: system randomness and
+/// `std::io` encoding. It is a separate trait so that the methods of
+/// [`BaseExt`] do not change with the `std` feature.
+#[cfg(feature = "std")]
+pub trait BaseExtStd: BaseExt {
+    /// This computes a random element of the field using system randomness.
+    fn rand() -> Self {
+        Self::random(rand::rngs::OsRng)
+    }
+
+    /// Writes this element in its normalized, little endian form into a buffer.
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads a normalized, little endian represented field element from a
+    /// buffer.
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
 pub trait FieldExt: ff::PrimeField + BaseExt + Group<Scalar = Self> {
     /// Inverse of $2$ in the field.
     const TWO_INV: Self;
@@ -0,0 +1,208 @@
+//! This module provides multi-scalar multiplication over the `CurveAffine`
+//! abstraction, using Pippenger's bucket method.
+
+use super::CurveAffine;
+use alloc::vec;
+use alloc::vec::Vec;
+use ff::PrimeField;
+use group::Group as _;
+
+/// Returns the bucket index for window `segment` of width `c` of the little
+/// endian scalar representation `bytes`.
+pub(crate) fn get_at(segment: usize, c: usize, bytes: &[u8]) -> usize {
+    let skip_bits = segment * c;
+    let skip_bytes = skip_bits / 8;
+
+    if skip_bytes >= bytes.len() {
+        return 0;
+    }
+
+    let mut v = [0; 8];
+    for (v, o) in v.iter_mut().zip(bytes[skip_bytes..].iter()) {
+        *v = *o;
+    }
+
+    let mut tmp = u64::from_le_bytes(v);
+    tmp >>= skip_bits - (skip_bytes * 8);
+    tmp %= 1 << c;
+
+    tmp as usize
+}
+
+/// Returns the window size used for `n` terms.
+///
+/// The cost of Pippenger's method is lowest around a window of `ln(n)` bits,
+/// which is estimated without floating point arithmetic as
+/// `ceil(floor(log2(n)) * ln(2))`. This matches `ceil(ln(n))` at powers of
+/// two and is at most one bit smaller in between.
+pub(crate) fn window_size(n: usize) -> usize {
+    if n < 4 {
+        1
+    } else if n < 32 {
+        3
+    } else {
+        // ln(2) ~ 693 / 1000
+        (n.ilog2() as usize * 693).div_ceil(1000)
+    }
+}
+
+/// A bucket of points that stays in affine form until a second point is
+/// added to it, so the first addition is an affine-affine one and the rest
+/// are mixed additions.
+#[derive(Clone, Copy)]
+enum Bucket<C: CurveAffine> {
+    None,
+    Affine(C),
+    Projective(C::Curve),
+}
+
+impl<C: CurveAffine> Bucket<C> {
+    fn add_assign(&mut self, other: &C) {
+        *self = match *self {
+            Bucket::None => Bucket::Affine(*other),
+            Bucket::Affine(a) => Bucket::Projective(a + *other),
+            Bucket::Projective(mut a) => {
+                a += *other;
+                Bucket::Projective(a)
+            }
+        }
+    }
+
+    fn add(self, mut other: C::Curve) -> C::Curve {
+        match self {
+            Bucket::None => other,
+            Bucket::Affine(a) => {
+                other += a;
+                other
+            }
+            Bucket::Projective(a) => other + a,
+        }
+    }
+}
+
+fn msm_serial<C: CurveAffine>(bases: &[C], scalars: &[C::ScalarExt]) -> C::Curve {
+    let scalars: Vec<_> = scalars.iter().map(|a| a.to_repr()).collect();
+
+    let c = window_size(bases.len());
+    let segments = (C::ScalarExt::NUM_BITS as usize).div_ceil(c);
+
+    let mut acc = C::Curve::identity();
+    for current_segment in (0..segments).rev() {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+
+        let mut buckets: Vec<Bucket<C>> = vec![Bucket::None; (1 << c) - 1];
+        for (scalar, base) in scalars.iter().zip(bases.iter()) {
+            let index = get_at(current_segment, c, scalar.as_ref());
+            if index != 0 {
+                buckets[index - 1].add_assign(base);
+            }
+        }
+
+        // Summation by parts
+        // e.g. 3a + 2b + 1c = a +
+        //                    (a) + b +
+        //                    ((a) + b) + c
+        let mut running_sum = C::Curve::identity();
+        for bucket in buckets.into_iter().rev() {
+            running_sum = bucket.add(running_sum);
+            acc += &running_sum;
+        }
+    }
+
+    acc
+}
+
+/// Computes $\sum_i s_i B_i$ for `bases` $B_i$ and `scalars` $s_i$ using
+/// windowed Pippenger. Panics if the slices differ in length.
+pub fn msm<C: CurveAffine>(bases: &[C], scalars: &[C::ScalarExt]) -> C::Curve {
+    assert_eq!(bases.len(), scalars.len());
+
+    msm_serial(bases, scalars)
+}
+
+/// Multi-threaded variant of [`msm`] that splits the terms into one chunk
+/// per thread and sums the partial results.
+#[cfg(feature = "multicore")]
+pub fn msm_parallel<C: CurveAffine>(bases: &[C], scalars: &[C::ScalarExt]) -> C::Curve {
+    use rayon::prelude::*;
+
+    assert_eq!(bases.len(), scalars.len());
+
+    let num_threads = rayon::current_num_threads();
+    if bases.len() <= num_threads {
+        return msm_serial(bases, scalars);
+    }
+
+    let chunk = bases.len().div_ceil(num_threads);
+    bases
+        .par_chunks(chunk)
+        .zip(scalars.par_chunks(chunk))
+        .map(|(bases, scalars)| msm_serial(bases, scalars))
+        .reduce(C::Curve::identity, |a, b| a + b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::CurveExt;
+    use crate::bn256::{G1, G2};
+    use ff::Field;
+    use group::prime::PrimeCurveAffine;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn msm_against_naive<G: CurveExt>() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for &n in [0, 1, 2, 3, 5, 31, 32, 100, 257].iter() {
+            let mut bases: Vec<G::AffineExt> = (0..n)
+                .map(|_| (G::generator() * G::ScalarExt::random(&mut rng)).into())
+                .collect();
+            let mut scalars: Vec<G::ScalarExt> =
+                (0..n).map(|_| G::ScalarExt::random(&mut rng)).collect();
+
+            // Exercise identities, zeros, ones and repeated bases.
+            if n > 3 {
+                bases[0] = G::AffineExt::identity();
+                scalars[1] = G::ScalarExt::zero();
+                scalars[2] = G::ScalarExt::one();
+                bases[3] = bases[2];
+            }
+
+            let expected = bases
+                .iter()
+                .zip(scalars.iter())
+                .fold(G::identity(), |acc, (b, s)| acc + *b * *s);
+
+            assert_eq!(msm(&bases, &scalars), expected);
+            #[cfg(feature = "multicore")]
+            assert_eq!(msm_parallel(&bases, &scalars), expected);
+        }
+    }
+
+    #[test]
+    fn test_window_size() {
+        for n in (32..100_000).chain([1 << 20, 3584912846, u32::MAX as usize]) {
+            let ln = (n as f64).ln();
+            let c = window_size(n);
+            assert!(c as f64 >= ln - 1.0 && c as f64 <= ln.ceil(), "n = {}", n);
+        }
+
+        // Exact at powers of two, with no upper bound on the window.
+        for k in 5..usize::BITS {
+            let n = 1usize << k;
+            assert_eq!(window_size(n), (n as f64).ln().ceil() as usize, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_msm() {
+        msm_against_naive::<G1>();
+        msm_against_naive::<G2>();
+    }
+}
@@ -118,7 +118,7 @@ macro_rules! common_field {
             }
         }
 
-        impl ::std::fmt::Display for $field {
+        impl ::core::fmt::Display for $field {
             fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
                 let tmp = self.to_repr();
                 write!(f, "0x")?;
@@ -179,23 +179,23 @@ macro_rules! common_field {
             }
         }
 
-        impl std::cmp::Ord for $field {
-            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        impl core::cmp::Ord for $field {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
                 let left = self.to_repr();
                 let right = other.to_repr();
                 left.iter()
                     .zip(right.iter())
                     .rev()
                     .find_map(|(left_byte, right_byte)| match left_byte.cmp(right_byte) {
-                        std::cmp::Ordering::Equal => None,
+                        core::cmp::Ordering::Equal => None,
                         res => Some(res),
                     })
-                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .unwrap_or(core::cmp::Ordering::Equal)
             }
         }
 
-        impl std::cmp::PartialOrd for $field {
-            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        impl core::cmp::PartialOrd for $field {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
                 Some(self.cmp(other))
             }
         }
@@ -457,7 +457,10 @@ macro_rules! common_field {
             fn ct_is_zero(&self) -> Choice {
                 self.ct_eq(&Self::zero())
             }
+        }
 
+        #[cfg(feature = "std")]
+        impl BaseExtStd for $field {
             /// Writes this element in its normalized, little endian form into a buffer.
             fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
                 let compressed = self.to_repr();
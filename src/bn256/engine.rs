@@ -1,18 +1,33 @@
-use crate::arithmetic::{Engine, MillerLoopResult, MultiMillerLoop, PairingCurveAffine};
+#[cfg(feature = "alloc")]
+use crate::arithmetic::{
+    affine_miller_loop, bn_multi_miller_loop, bn_multi_miller_loop_affine, get_at, window_size,
+    BnG2Prepared, Engine, MultiMillerLoop, PairingCurveAffine,
+};
+use crate::arithmetic::{bn_final_exponentiation, BnParameters, MillerLoopResult, TwistType};
 use crate::bn256::fq::*;
 use crate::bn256::fq12::*;
 use crate::bn256::fq2::*;
-use crate::bn256::fq6::FROBENIUS_COEFF_FQ6_C1;
+use crate::bn256::fq6::{Fq6, FROBENIUS_COEFF_FQ6_C1};
 use crate::bn256::fr::*;
 use crate::bn256::g::*;
+use crate::bn256::g::{gls_decompose, GLS_BITS, SIX_X_SQUARED};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
 use core::borrow::Borrow;
-use core::iter::Sum;
-use core::ops::{Add, Mul, MulAssign, Neg, Sub};
-use ff::{Field, PrimeField};
-use group::cofactor::CofactorCurveAffine;
+#[cfg(feature = "alloc")]
+use core::convert::TryInto;
+use core::iter::{Product, Sum};
+#[cfg(feature = "alloc")]
+use core::ops::MulAssign;
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
 use group::Group;
+#[cfg(feature = "alloc")]
+use group::{cofactor::CofactorCurveAffine, Curve, UncompressedEncoding};
 use rand_core::RngCore;
-use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+#[cfg(feature = "alloc")]
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 pub const BN_X: u64 = 4965661367192848881;
 
@@ -38,6 +53,7 @@ pub const XI_TO_Q_MINUS_1_OVER_2: Fq2 = Fq2 {
     ]),
 };
 
+#[cfg(feature = "alloc")]
 impl PairingCurveAffine for G1Affine {
     type Pair = G2Affine;
     type PairingResult = Gt;
@@ -47,6 +63,7 @@ impl PairingCurveAffine for G1Affine {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl PairingCurveAffine for G2Affine {
     type Pair = G1Affine;
     type PairingResult = Gt;
@@ -59,8 +76,101 @@ impl PairingCurveAffine for G2Affine {
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Gt(pub(crate) Fq12);
 
-impl std::fmt::Display for Gt {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// The generator of $\mathbb{G}_T$, computed as $e(G_1, G_2)$ for the fixed
+/// generators of $\mathbb{G}_1$ and $\mathbb{G}_2$.
+pub const GT_GENERATOR: Gt = Gt(Fq12 {
+    c0: Fq6 {
+        c0: Fq2 {
+            c0: Fq([
+                0xc556f62b2a98671d,
+                0x23a59ac167bcf363,
+                0x5ef208445f5f6f37,
+                0x12adf27ccb29382a,
+            ]),
+            c1: Fq([
+                0x2e02a64acbd60549,
+                0xd618018ea58e4add,
+                0x14d585f1a45ba647,
+                0x1832226987c434fc,
+            ]),
+        },
+        c1: Fq2 {
+            c0: Fq([
+                0x2306e4312363b991,
+                0x465f6072d4023bf4,
+                0xa2ff062a4a77e736,
+                0x076ea6f18435864a,
+            ]),
+            c1: Fq([
+                0x172d1f257a4d598e,
+                0xddf5bc7b7ffb5ac0,
+                0xae0b22c0bbb0f602,
+                0x1b158f3c2fae9b18,
+            ]),
+        },
+        c2: Fq2 {
+            c0: Fq([
+                0x5cf9cc917da86724,
+                0xc799dc487a0b2753,
+                0x0df2027bf1de17a7,
+                0x197cda6cc3e20636,
+            ]),
+            c1: Fq([
+                0xf16c96d081754cdb,
+                0xce0394312bceeb55,
+                0x644e4dcf1f01ff0a,
+                0x0cbea85ee0b236cc,
+            ]),
+        },
+    },
+    c1: Fq6 {
+        c0: Fq2 {
+            c0: Fq([
+                0x1bb0ce0def1b82a1,
+                0x4c4c9fe1cadefa95,
+                0x746d9990cb12b27e,
+                0x13495c08e5d415c5,
+            ]),
+            c1: Fq([
+                0x9458abcb56d24998,
+                0xb17540bd2a9e5adb,
+                0x9a9983c82e401a9f,
+                0x1614817a84c16291,
+            ]),
+        },
+        c1: Fq2 {
+            c0: Fq([
+                0x8975b68a2bab1f9c,
+                0x2fdd826b796e0f35,
+                0x6a90a35fa03dfaa5,
+                0x1ffef4581607fc37,
+            ]),
+            c1: Fq([
+                0x7002907c28ebfe11,
+                0x7b0591d3d080da67,
+                0xde7e5aa2181f138e,
+                0x210e437dfc43d951,
+            ]),
+        },
+        c2: Fq2 {
+            c0: Fq([
+                0x988ae2485b36cf53,
+                0x5091cc0581334e54,
+                0xda7903229312ca0f,
+                0x2a2341538eaee95c,
+            ]),
+            c1: Fq([
+                0xd34bab373157aa84,
+                0x3511ed44fd0d8598,
+                0x67e42a0bc2ced972,
+                0x2b8f1d5dfd20c55b,
+            ]),
+        },
+    },
+});
+
+impl core::fmt::Display for Gt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
@@ -91,10 +201,150 @@ impl Gt {
         Gt(Fq12::one())
     }
 
+    /// Returns the fixed generator $e(G_1, G_2)$ of the group.
+    pub fn generator() -> Gt {
+        GT_GENERATOR
+    }
+
     /// Doubles this group element.
     pub fn double(&self) -> Gt {
         Gt(self.0.square())
     }
+
+    /// Exponentiates this element by `exp`, given as little-endian limbs, in
+    /// variable time using compressed cyclotomic squarings.
+    pub fn pow_vartime<S: AsRef<[u64]>>(&self, exp: S) -> Gt {
+        Gt(self.0.cyclotomic_exp_vartime(exp.as_ref()))
+    }
+
+    /// Multiplies this element by `by` in variable time, see the constant time
+    /// `Mul<Fr>` implementation for the method.
+    pub fn mul_vartime(&self, by: &Fr) -> Gt {
+        let ks = gls_decompose(by);
+        let table = self.gls_table(&ks);
+
+        let index = |i: usize| {
+            ks.iter().enumerate().fold(0usize, |index, (j, (k, _))| {
+                index | ((((k >> i) & 1) as usize) << j)
+            })
+        };
+
+        let mut acc = Fq12::one();
+        if let Some(top) = (0..GLS_BITS).rev().find(|&i| index(i) != 0) {
+            acc = table[index(top)];
+            for i in (0..top).rev() {
+                acc.cyclotomic_square();
+                let index = index(i);
+                if index != 0 {
+                    acc.mul_assign(&table[index]);
+                }
+            }
+        }
+
+        Gt(acc)
+    }
+
+    /// Returns the products of all subsets of this element and its images under
+    /// the first three powers of the Frobenius map, conjugated according to the
+    /// signs of the GLS decomposition `ks`. On the group the Frobenius map is
+    /// exponentiation by q, and q = 6x^2 mod r is the eigenvalue used by
+    /// [`gls_decompose`], so the table can be indexed by the bits of the `k_i`.
+    fn gls_table(&self, ks: &[(u128, Choice); 4]) -> [Fq12; 16] {
+        let mut bases = [self.0; 4];
+        for i in 1..4 {
+            bases[i] = bases[i - 1];
+            bases[i].frobenius_map(1);
+        }
+        for (base, (_, is_neg)) in bases.iter_mut().zip(ks.iter()) {
+            let mut conj = *base;
+            conj.conjugate();
+            base.conditional_assign(&conj, *is_neg);
+        }
+
+        // table[i] is the product of the bases selected by the bits of i.
+        let mut table = [Fq12::one(); 16];
+        for i in 1..16usize {
+            let low = i.trailing_zeros() as usize;
+            table[i] = table[i & (i - 1)] * bases[low];
+        }
+
+        table
+    }
+
+    /// Converts this element into the 384 byte encoding of the underlying
+    /// `Fq12`, its coefficients `c0`, `c1` in little-endian byte order.
+    pub fn to_bytes(&self) -> [u8; 384] {
+        self.0.to_bytes()
+    }
+
+    /// Attempts to convert the encoding of [`Gt::to_bytes`] back into an
+    /// element, failing if a coefficient is not canonical or if the element
+    /// is not in the cyclotomic subgroup of order $r$.
+    pub fn from_bytes(bytes: &[u8; 384]) -> CtOption<Gt> {
+        Fq12::from_bytes(bytes).and_then(|f| CtOption::new(Gt(f), Gt::is_element(&f)))
+    }
+
+    /// Compresses this element into 192 bytes using the algebraic torus $T_2$.
+    ///
+    /// Writing the element as $c_0 + c_1 w$ with $w^2 = v$, it has norm one over
+    /// `Fq6` and is therefore determined by $g = (1 + c_0) / c_1$ through
+    /// $c_0 + c_1 w = (g + w) / (g - w)$. The identity, the only element of the
+    /// group with $c_1 = 0$, is encoded as $g = 0$.
+    pub fn to_compressed(&self) -> [u8; 192] {
+        let f = &self.0;
+        let g = (f.c0 + Fq6::one()) * f.c1.invert().unwrap_or_else(Fq6::zero);
+        g.to_bytes()
+    }
+
+    /// Attempts to decompress an element from the encoding of
+    /// [`Gt::to_compressed`], failing if the encoding is not canonical or does
+    /// not decompress to an element of the group.
+    pub fn from_compressed(bytes: &[u8; 192]) -> CtOption<Gt> {
+        Fq6::from_bytes(bytes).and_then(|g| {
+            let v = Fq6 {
+                c0: Fq2::zero(),
+                c1: Fq2::one(),
+                c2: Fq2::zero(),
+            };
+            let g2 = g.square();
+
+            // (g + w) / (g - w) = (g^2 + v + 2gw) / (g^2 - v), where g^2 - v is
+            // never zero as v is not a square in Fq6.
+            (g2 - v).invert().and_then(|d| {
+                let f = Fq12 {
+                    c0: (g2 + v) * d,
+                    c1: g.double() * d,
+                };
+                let f = Fq12::conditional_select(&f, &Fq12::one(), g.is_zero());
+                CtOption::new(Gt(f), Gt::is_element(&f))
+            })
+        })
+    }
+
+    /// Returns whether `f` lies in the order $r$ subgroup of `Fq12^*`.
+    fn is_element(f: &Fq12) -> Choice {
+        // A non-zero f is in the cyclotomic subgroup, of order q^4 - q^2 + 1, if
+        // and only if f^(q^4) * f == f^(q^2).
+        let mut fq2 = *f;
+        fq2.frobenius_map(2);
+        let mut fq4 = fq2;
+        fq4.frobenius_map(2);
+        let cyclotomic = (fq4 * f).ct_eq(&fq2);
+
+        // Within the cyclotomic subgroup f has order r if and only if
+        // f^q == f^(6x^2), see https://eprint.iacr.org/2022/348.pdf
+        let mut fq = *f;
+        fq.frobenius_map(1);
+        let mut f6x2 = Fq12::one();
+        for i in (0..128).rev() {
+            f6x2.cyclotomic_square();
+            let mut tmp = f6x2;
+            tmp.mul_assign(f);
+            f6x2.conditional_assign(&tmp, (((SIX_X_SQUARED >> i) & 1) as u8).into());
+        }
+
+        !f.is_zero() & cyclotomic & fq.ct_eq(&f6x2)
+    }
 }
 
 impl<'a> Neg for &'a Gt {
@@ -139,21 +389,33 @@ impl<'a, 'b> Sub<&'b Gt> for &'a Gt {
 impl<'a, 'b> Mul<&'b Fr> for &'a Gt {
     type Output = Gt;
 
+    /// Multiplies by `other` in constant time using the Frobenius map: the
+    /// scalar is split into four parts of about 64 bits that are applied to
+    /// the element and its images under the Frobenius map with a joint
+    /// square-and-multiply over a table of all 16 subset products, using
+    /// cyclotomic squarings.
     fn mul(self, other: &'b Fr) -> Self::Output {
-        let mut acc = Gt::identity();
-
-        for bit in other
-            .to_repr()
-            .iter()
-            .rev()
-            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-            .skip(1)
-        {
-            acc = acc.double();
-            acc = Gt::conditional_select(&acc, &(acc + self), bit);
+        let ks = gls_decompose(other);
+        let table = self.gls_table(&ks);
+
+        let mut acc = Fq12::one();
+        for i in (0..GLS_BITS).rev() {
+            acc.cyclotomic_square();
+
+            let index = ks.iter().enumerate().fold(0u8, |index, (j, (k, _))| {
+                index | ((((k >> i) & 1) as u8) << j)
+            });
+
+            // Scan the whole table so the memory access pattern does not
+            // depend on the scalar, table[0] being one.
+            let mut factor = Fq12::one();
+            for (j, entry) in table.iter().enumerate() {
+                factor.conditional_assign(entry, index.ct_eq(&(j as u8)));
+            }
+            acc.mul_assign(&factor);
         }
 
-        acc
+        Gt(acc)
     }
 }
 
@@ -172,11 +434,88 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+impl Gt {
+    /// Computes $\sum_i s_i g_i$ for `bases` $g_i$ and `scalars` $s_i$, the
+    /// product of the $g_i^{s_i}$ in multiplicative notation, in variable time.
+    /// Few terms are multiplied separately with [`Gt::mul_vartime`], more with
+    /// windowed Pippenger over the four part GLS decompositions of the scalars
+    /// using cyclotomic squarings. Panics if the slices differ in length.
+    pub fn multiexp(bases: &[Gt], scalars: &[Fr]) -> Gt {
+        assert_eq!(bases.len(), scalars.len());
+
+        if bases.len() < MULTIEXP_THRESHOLD {
+            return bases
+                .iter()
+                .zip(scalars.iter())
+                .map(|(base, scalar)| base.mul_vartime(scalar))
+                .sum();
+        }
+
+        // Split every term into four with the GLS decomposition, as in
+        // `Gt::mul_vartime`, so that the scalars are only GLS_BITS long.
+        let mut split_bases = Vec::with_capacity(4 * bases.len());
+        let mut split_scalars = Vec::with_capacity(4 * bases.len());
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            let mut base = base.0;
+            for (k, is_neg) in gls_decompose(scalar) {
+                let mut b = base;
+                if bool::from(is_neg) {
+                    b.conjugate();
+                }
+                split_bases.push(b);
+                split_scalars.push(k.to_le_bytes());
+                base.frobenius_map(1);
+            }
+        }
+        let (bases, scalars) = (split_bases, split_scalars);
+
+        let c = window_size(bases.len());
+        let segments = GLS_BITS.div_ceil(c);
+
+        let mut acc = Fq12::one();
+        for current_segment in (0..segments).rev() {
+            for _ in 0..c {
+                acc.cyclotomic_square();
+            }
+
+            let mut buckets: Vec<Option<Fq12>> = vec![None; (1 << c) - 1];
+            for (scalar, base) in scalars.iter().zip(bases.iter()) {
+                let index = get_at(current_segment, c, scalar.as_ref());
+                if index != 0 {
+                    let bucket = &mut buckets[index - 1];
+                    *bucket = Some(bucket.map_or(*base, |b| b * base));
+                }
+            }
+
+            // Summation by parts, see msm.
+            let mut running_product: Option<Fq12> = None;
+            for bucket in buckets.into_iter().rev() {
+                running_product = match (running_product, bucket) {
+                    (Some(p), Some(b)) => Some(p * b),
+                    (p, b) => p.or(b),
+                };
+                if let Some(p) = running_product {
+                    acc.mul_assign(&p);
+                }
+            }
+        }
+
+        Gt(acc)
+    }
+}
+
+/// Below this many terms [`Gt::multiexp`] multiplies them separately.
+#[cfg(feature = "alloc")]
+const MULTIEXP_THRESHOLD: usize = 8;
+
 impl Group for Gt {
     type Scalar = Fr;
 
-    fn random(_: impl RngCore) -> Self {
-        unimplemented!();
+    fn random(mut rng: impl RngCore) -> Self {
+        // The generator has prime order r, so a uniform exponent yields a
+        // uniform element of the subgroup.
+        Self::generator() * Fr::random(&mut rng)
     }
 
     fn identity() -> Self {
@@ -184,7 +523,7 @@ impl Group for Gt {
     }
 
     fn generator() -> Self {
-        unimplemented!();
+        Self::generator()
     }
 
     fn is_identity(&self) -> Choice {
@@ -197,426 +536,262 @@ impl Group for Gt {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct G2Prepared {
-    pub(crate) coeffs: Vec<(Fq2, Fq2, Fq2)>,
+/// Number of line coefficients of a prepared G2 point other than the identity:
+/// a doubling step per NAF digit, an addition step per non-zero digit and the
+/// two final addition steps.
+pub const G2_PREPARED_COEFFS: usize = {
+    let mut n = SIX_U_PLUS_2_NAF.len() - 1 + 2;
+    let mut i = 0;
+    while i < SIX_U_PLUS_2_NAF.len() - 1 {
+        if SIX_U_PLUS_2_NAF[i] != 0 {
+            n += 1;
+        }
+        i += 1;
+    }
+    n
+};
+
+/// A G1 point prepared for [`multi_miller_loop_g1_prepared`], holding $-x/y$
+/// and $1/y$ so that the lines can be normalized to a constant coefficient
+/// of one.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug)]
+pub struct G1Prepared {
+    pub(crate) neg_x_over_y: Fq,
+    pub(crate) y_inverse: Fq,
     pub(crate) infinity: bool,
 }
 
-impl G2Prepared {
+#[cfg(feature = "alloc")]
+impl G1Prepared {
     pub fn is_zero(&self) -> bool {
         self.infinity
     }
 
-    pub fn from_affine(q: G2Affine) -> Self {
-        if bool::from(q.is_identity()) {
-            return G2Prepared {
-                coeffs: vec![],
-                infinity: true,
-            };
+    pub fn from_affine(p: G1Affine) -> Self {
+        // There is no point with y = 0 as G1 has odd order, so the inverse only
+        // fails at the identity.
+        let y_inverse = p.y.invert().unwrap_or(Fq::zero());
+        G1Prepared {
+            neg_x_over_y: -(p.x * y_inverse),
+            y_inverse,
+            infinity: bool::from(p.is_identity()),
         }
+    }
+}
 
-        fn doubling_step(r: &mut G2) -> (Fq2, Fq2, Fq2) {
-            // Adaptation of Algorithm 26, https://eprint.iacr.org/2010/354.pdf
-            let mut tmp0 = r.x;
-            tmp0.square_assign();
-
-            let mut tmp1 = r.y;
-            tmp1.square_assign();
-
-            let mut tmp2 = tmp1;
-            tmp2.square_assign();
-
-            let mut tmp3 = tmp1;
-            tmp3 += &r.x;
-            tmp3.square_assign();
-            tmp3 -= &tmp0;
-            tmp3 -= &tmp2;
-            tmp3.double_assign();
-
-            let mut tmp4 = tmp0;
-            tmp4.double_assign();
-            tmp4 += &tmp0;
-
-            let mut tmp6 = r.x;
-            tmp6 += &tmp4;
-
-            let mut tmp5 = tmp4;
-            tmp5.square_assign();
-
-            let mut zsquared = r.z;
-            zsquared.square_assign();
-
-            r.x = tmp5;
-            r.x -= &tmp3;
-            r.x -= &tmp3;
-
-            r.z += &r.y;
-            r.z.square_assign();
-            r.z -= &tmp1;
-            r.z -= &zsquared;
-
-            r.y = tmp3;
-            r.y -= &r.x;
-            r.y.mul_assign(&tmp4);
-
-            tmp2.double_assign();
-            tmp2.double_assign();
-            tmp2.double_assign();
-
-            r.y -= &tmp2;
-
-            // up to here everything was by algorith, line 11
-            // use R instead of new T
+#[cfg(feature = "alloc")]
+impl From<G1Affine> for G1Prepared {
+    fn from(p: G1Affine) -> G1Prepared {
+        G1Prepared::from_affine(p)
+    }
+}
 
-            // tmp3 is the first part of line 12
-            tmp3 = tmp4;
-            tmp3.mul_assign(&zsquared);
-            tmp3.double_assign();
-            tmp3 = tmp3.neg();
+/// Length of the encoding of a prepared point other than the identity, see
+/// [`G2Prepared::to_bytes`].
+pub const G2_PREPARED_BYTES: usize = G2_PREPARED_HEADER_BYTES + G2_PREPARED_COEFFS * 3 * 64;
 
-            // tmp6 is from line 14
-            tmp6.square_assign();
-            tmp6 -= &tmp0;
-            tmp6 -= &tmp5;
+/// Length of the uncompressed source point and the checksum that start the
+/// encoding of a prepared point, and the whole encoding of the identity.
+const G2_PREPARED_HEADER_BYTES: usize = 128 + 32;
 
-            tmp1.double_assign();
-            tmp1.double_assign();
+/// A G2 point with the line coefficients of the Miller loop precomputed.
+#[cfg(feature = "alloc")]
+pub type G2Prepared = BnG2Prepared<Bn256>;
 
-            tmp6 -= &tmp1;
+#[cfg(feature = "alloc")]
+impl G2Prepared {
+    /// Encodes this prepared point as the uncompressed source point, a
+    /// SHA-256 checksum of the rest of the encoding and the line coefficients,
+    /// for a total of [`G2_PREPARED_BYTES`] bytes, or only the first two for
+    /// the identity.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(G2_PREPARED_HEADER_BYTES + self.coeffs.len() * 3 * 64);
+        bytes.extend_from_slice(self.point.to_uncompressed().as_ref());
+        bytes.extend_from_slice(&[0u8; 32]);
+        for (c0, c1, c2) in self.coeffs.iter() {
+            bytes.extend_from_slice(&c0.to_bytes());
+            bytes.extend_from_slice(&c1.to_bytes());
+            bytes.extend_from_slice(&c2.to_bytes());
+        }
 
-            // tmp0 is the first part of line 16
-            tmp0 = r.z;
-            tmp0.mul_assign(&zsquared);
-            tmp0.double_assign();
+        let checksum = G2Prepared::checksum(&bytes);
+        bytes[128..G2_PREPARED_HEADER_BYTES].copy_from_slice(&checksum);
+        bytes
+    }
 
-            (tmp0, tmp3, tmp6)
+    /// Attempts to decode the encoding of [`G2Prepared::to_bytes`], failing if
+    /// the length does not match the source point, if the checksum does not
+    /// match, if the source point is not on the curve or not in the subgroup,
+    /// or if the coefficients are not those of the source point.
+    ///
+    /// The checksum is not keyed, so it only catches accidental corruption
+    /// cheaply. The coefficients are checked by preparing the source point
+    /// again, which costs about as much as [`G2Prepared::from_affine`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<G2Prepared> {
+        if bytes.len() != G2_PREPARED_HEADER_BYTES && bytes.len() != G2_PREPARED_BYTES {
+            return None;
         }
-
-        fn addition_step(r: &mut G2, q: &G2Affine) -> (Fq2, Fq2, Fq2) {
-            // Adaptation of Algorithm 27, https://eprint.iacr.org/2010/354.pdf
-            let mut zsquared = r.z;
-            zsquared.square_assign();
-
-            let mut ysquared = q.y;
-            ysquared.square_assign();
-
-            // t0 corresponds to line 1
-            let mut t0 = zsquared;
-            t0.mul_assign(&q.x);
-
-            // t1 corresponds to lines 2 and 3
-            let mut t1 = q.y;
-            t1 += &r.z;
-            t1.square_assign();
-            t1 -= &ysquared;
-            t1 -= &zsquared;
-            t1.mul_assign(&zsquared);
-
-            // t2 corresponds to line 4
-            let mut t2 = t0;
-            t2 -= &r.x;
-
-            // t3 corresponds to line 5
-            let mut t3 = t2;
-            t3.square_assign();
-
-            // t4 corresponds to line 6
-            let mut t4 = t3;
-            t4.double_assign();
-            t4.double_assign();
-
-            // t5 corresponds to line 7
-            let mut t5 = t4;
-            t5.mul_assign(&t2);
-
-            // t6 corresponds to line 8
-            let mut t6 = t1;
-            t6 -= &r.y;
-            t6 -= &r.y;
-
-            // t9 corresponds to line 9
-            let mut t9 = t6;
-            t9.mul_assign(&q.x);
-
-            // corresponds to line 10
-            let mut t7 = t4;
-            t7.mul_assign(&r.x);
-
-            // corresponds to line 11, but assigns to r.x instead of T.x
-            r.x = t6;
-            r.x.square_assign();
-            r.x -= &t5;
-            r.x -= &t7;
-            r.x -= &t7;
-
-            // corresponds to line 12, but assigns to r.z instead of T.z
-            r.z += &t2;
-            r.z.square_assign();
-            r.z -= &zsquared;
-            r.z -= &t3;
-
-            // corresponds to line 13
-            let mut t10 = q.y;
-            t10 += &r.z;
-
-            // corresponds to line 14
-            let mut t8 = t7;
-            t8 -= &r.x;
-            t8.mul_assign(&t6);
-
-            // corresponds to line 15
-            t0 = r.y;
-            t0.mul_assign(&t5);
-            t0.double_assign();
-
-            // corresponds to line 12, but assigns to r.y instead of T.y
-            r.y = t8;
-            r.y -= &t0;
-
-            // corresponds to line 17
-            t10.square_assign();
-            t10 -= &ysquared;
-
-            let mut ztsquared = r.z;
-            ztsquared.square_assign();
-
-            t10 -= &ztsquared;
-
-            // corresponds to line 18
-            t9.double_assign();
-            t9 -= &t10;
-
-            // t10 = 2*Zt from Algo 27, line 19
-            t10 = r.z;
-            t10.double_assign();
-
-            // t1 = first multiplicator of line 21
-            t6 = t6.neg();
-
-            t1 = t6;
-            t1.double_assign();
-
-            // t9 corresponds to t9 from Algo 27
-            (t10, t1, t9)
+        if bytes[128..G2_PREPARED_HEADER_BYTES] != G2Prepared::checksum(bytes) {
+            return None;
         }
 
-        let mut coeffs = vec![];
-        let mut r: G2 = q.into();
-
-        let mut negq = q;
-        negq = -negq;
-
-        for i in (1..SIX_U_PLUS_2_NAF.len()).rev() {
-            coeffs.push(doubling_step(&mut r));
-            let x = SIX_U_PLUS_2_NAF[i - 1];
-            match x {
-                1 => {
-                    coeffs.push(addition_step(&mut r, &q));
-                }
-                -1 => {
-                    coeffs.push(addition_step(&mut r, &negq));
-                }
-                _ => continue,
-            }
+        let mut repr = G2Uncompressed::default();
+        repr.as_mut().copy_from_slice(&bytes[..128]);
+        let point: G2Affine = Option::from(G2Affine::from_uncompressed(&repr))?;
+        let infinity = bool::from(point.is_identity());
+        if infinity != (bytes.len() == G2_PREPARED_HEADER_BYTES) {
+            return None;
         }
 
-        let mut q1 = q;
-
-        q1.x.c1 = q1.x.c1.neg();
-        q1.x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[1]);
-
-        q1.y.c1 = q1.y.c1.neg();
-        q1.y.mul_assign(&XI_TO_Q_MINUS_1_OVER_2);
+        let fq2 = |bytes: &[u8]| Option::<Fq2>::from(Fq2::from_bytes(bytes.try_into().unwrap()));
+        let coeffs = bytes[G2_PREPARED_HEADER_BYTES..]
+            .chunks(3 * 64)
+            .map(|c| Some((fq2(&c[..64])?, fq2(&c[64..128])?, fq2(&c[128..])?)))
+            .collect::<Option<Vec<_>>>()?;
 
-        coeffs.push(addition_step(&mut r, &q1));
-
-        let mut minusq2 = q;
-        minusq2.x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[2]);
+        let prepared = G2Prepared::from_affine(point);
+        if prepared.coeffs != coeffs {
+            return None;
+        }
 
-        coeffs.push(addition_step(&mut r, &minusq2));
+        Some(prepared)
+    }
 
-        G2Prepared {
-            coeffs,
-            infinity: false,
-        }
+    /// Returns the SHA-256 digest of an encoding of a prepared point, leaving
+    /// out the checksum itself.
+    fn checksum(bytes: &[u8]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(&bytes[..128])
+            .chain_update(&bytes[G2_PREPARED_HEADER_BYTES..])
+            .finalize()
+            .into()
     }
 }
 
+#[cfg(feature = "alloc")]
 impl From<G2Affine> for G2Prepared {
     fn from(q: G2Affine) -> G2Prepared {
         G2Prepared::from_affine(q)
     }
 }
 
-impl MillerLoopResult for Gt {
-    type Gt = Self;
-    // pub fn final_exponentiation(r: &Fq12) -> CtOption<Fq12> {
-    fn final_exponentiation(&self) -> Gt {
-        fn exp_by_x(f: &mut Fq12) {
-            let x = BN_X;
-            let mut res = Fq12::one();
-            for i in (0..64).rev() {
-                res.cyclotomic_square();
-                if ((x >> i) & 1) == 1 {
-                    res.mul_assign(f);
-                }
-            }
-            *f = res;
-        }
-
-        let r = self.0;
-        let mut f1 = self.0;
-        f1.conjugate();
-
-        Gt(r.invert()
-            .map(|mut f2| {
-                let mut r = f1;
-                r.mul_assign(&f2);
-                f2 = r;
-                r.frobenius_map(2);
-                r.mul_assign(&f2);
-
-                let mut fp = r;
-                fp.frobenius_map(1);
-
-                let mut fp2 = r;
-                fp2.frobenius_map(2);
-                let mut fp3 = fp2;
-                fp3.frobenius_map(1);
-
-                let mut fu = r;
-                exp_by_x(&mut fu);
-
-                let mut fu2 = fu;
-                exp_by_x(&mut fu2);
-
-                let mut fu3 = fu2;
-                exp_by_x(&mut fu3);
-
-                let mut y3 = fu;
-                y3.frobenius_map(1);
-
-                let mut fu2p = fu2;
-                fu2p.frobenius_map(1);
-
-                let mut fu3p = fu3;
-                fu3p.frobenius_map(1);
-
-                let mut y2 = fu2;
-                y2.frobenius_map(2);
-
-                let mut y0 = fp;
-                y0.mul_assign(&fp2);
-                y0.mul_assign(&fp3);
-
-                let mut y1 = r;
-                y1.conjugate();
-
-                let mut y5 = fu2;
-                y5.conjugate();
-
-                y3.conjugate();
-
-                let mut y4 = fu;
-                y4.mul_assign(&fu2p);
-                y4.conjugate();
-
-                let mut y6 = fu3;
-                y6.mul_assign(&fu3p);
-                y6.conjugate();
-
-                y6.cyclotomic_square();
-                y6.mul_assign(&y4);
-                y6.mul_assign(&y5);
-
-                let mut t1 = y3;
-                t1.mul_assign(&y5);
-                t1.mul_assign(&y6);
-
-                y6.mul_assign(&y2);
-
-                t1.cyclotomic_square();
-                t1.mul_assign(&y6);
-                t1.cyclotomic_square();
-
-                let mut t0 = t1;
-                t0.mul_assign(&y1);
-
-                t1.mul_assign(&y0);
-
-                t0.cyclotomic_square();
-                t0.mul_assign(&t1);
-
-                t0
-            })
-            .unwrap())
+/// The result of a Miller loop. It is not an element of [`Gt`] until
+/// [`MillerLoopResult::final_exponentiation`] is applied, so it cannot be
+/// compared, but results of several loops can be multiplied together and
+/// exponentiated once.
+#[derive(Copy, Clone, Debug)]
+pub struct MillerLoopOutput(pub(crate) Fq12);
+
+impl MillerLoopOutput {
+    /// Returns the result of a Miller loop over no terms, which is $1$.
+    pub fn one() -> MillerLoopOutput {
+        MillerLoopOutput(Fq12::one())
     }
 }
 
-pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> Gt {
-    let mut pairs = vec![];
-    for &(p, q) in terms {
-        if !bool::from(p.is_identity()) && !bool::from(q.is_zero()) {
-            pairs.push((p, q.coeffs.iter()));
-        }
+impl Mul<&MillerLoopOutput> for &MillerLoopOutput {
+    type Output = MillerLoopOutput;
+
+    #[inline]
+    fn mul(self, rhs: &MillerLoopOutput) -> MillerLoopOutput {
+        MillerLoopOutput(self.0 * rhs.0)
     }
+}
 
-    // Final steps of the line function on prepared coefficients
-    fn ell(f: &mut Fq12, coeffs: &(Fq2, Fq2, Fq2), p: &G1Affine) {
-        let mut c0 = coeffs.0;
-        let mut c1 = coeffs.1;
+impl_binops_multiplicative!(MillerLoopOutput, MillerLoopOutput);
 
-        c0.c0.mul_assign(&p.y);
-        c0.c1.mul_assign(&p.y);
+impl<T> Product<T> for MillerLoopOutput
+where
+    T: Borrow<MillerLoopOutput>,
+{
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        iter.fold(Self::one(), |acc, item| acc * item.borrow())
+    }
+}
 
-        c1.c0.mul_assign(&p.x);
-        c1.c1.mul_assign(&p.x);
+impl MillerLoopResult for MillerLoopOutput {
+    type Gt = Gt;
 
-        // Sparse multiplication in Fq12
-        f.mul_by_034(&c0, &c1, &coeffs.2);
+    /// Raises this result to the power $(q^{12} - 1) / r$. A result of zero,
+    /// which the lines of points in G1 and G2 never produce but the lines of
+    /// a point prepared from an invalid [`G2Affine`] can, is mapped to zero
+    /// rather than to an element of [`Gt`], so it never passes a pairing
+    /// check.
+    fn final_exponentiation(&self) -> Gt {
+        Gt(bn_final_exponentiation::<Bn256>(&self.0).unwrap_or(Fq12::zero()))
     }
+}
 
-    let mut f = Fq12::one();
+#[cfg(feature = "alloc")]
+pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> MillerLoopOutput {
+    MillerLoopOutput(bn_multi_miller_loop::<Bn256>(terms))
+}
 
-    for i in (1..SIX_U_PLUS_2_NAF.len()).rev() {
-        if i != SIX_U_PLUS_2_NAF.len() - 1 {
-            f.square_assign();
-        }
-        for &mut (p, ref mut coeffs) in &mut pairs {
-            ell(&mut f, coeffs.next().unwrap(), &p);
-        }
-        let x = SIX_U_PLUS_2_NAF[i - 1];
-        match x {
-            1 => {
-                for &mut (p, ref mut coeffs) in &mut pairs {
-                    ell(&mut f, coeffs.next().unwrap(), &p);
-                }
-            }
-            -1 => {
-                for &mut (p, ref mut coeffs) in &mut pairs {
-                    ell(&mut f, coeffs.next().unwrap(), &p);
-                }
-            }
-            _ => continue,
-        }
-    }
+/// Computes the same Miller loop as [`multi_miller_loop`], up to a factor that
+/// vanishes in the final exponentiation, for G2 points that are not prepared.
+///
+/// The lines are evaluated on the fly while the multiples of every $Q_i$ are
+/// kept in affine coordinates, with the inversions of each step of the loop
+/// batched across all of the terms. This saves the precomputation and the
+/// allocation of a [`G2Prepared`] for points that are only used once, but
+/// every step still pays for an inversion in `Fq2`, so it is only faster than
+/// preparing the points when there are many terms.
+#[cfg(feature = "alloc")]
+pub fn multi_miller_loop_affine(terms: &[(&G1Affine, &G2Affine)]) -> MillerLoopOutput {
+    MillerLoopOutput(bn_multi_miller_loop_affine::<Bn256>(terms))
+}
 
-    for &mut (p, ref mut coeffs) in &mut pairs {
-        ell(&mut f, coeffs.next().unwrap(), &p);
-    }
+/// Same as [`multi_miller_loop_affine`] for prepared G1 points, which saves
+/// a multiplication in `Fq6` per line.
+#[cfg(feature = "alloc")]
+pub fn multi_miller_loop_g1_prepared(terms: &[(&G1Prepared, &G2Affine)]) -> MillerLoopOutput {
+    let pairs = terms
+        .iter()
+        .filter(|(p, q)| !p.is_zero() && !bool::from(q.is_identity()))
+        .map(|&(p, q)| (*p, q));
+
+    // The line of multi_miller_loop_affine divided by y_P, that is
+    // 1 + lambda * (-x_P / y_P) * w + c / y_P * v * w.
+    MillerLoopOutput(affine_miller_loop::<Bn256, _, _, _>(
+        pairs,
+        |f, p, lambda, c| {
+            let mut c3 = *lambda;
+            c3.c0.mul_assign(&p.neg_x_over_y);
+            c3.c1.mul_assign(&p.neg_x_over_y);
+
+            let mut c4 = *c;
+            c4.c0.mul_assign(&p.y_inverse);
+            c4.c1.mul_assign(&p.y_inverse);
+
+            f.mul_by_34(&c3, &c4);
+        },
+    ))
+}
 
-    for &mut (p, ref mut coeffs) in &mut pairs {
-        ell(&mut f, coeffs.next().unwrap(), &p);
-    }
+/// Multi-threaded variant of [`multi_miller_loop`] that splits the terms into
+/// one chunk per thread and multiplies the results of the chunks, which
+/// matches the serial result and still needs a single final exponentiation.
+#[cfg(feature = "multicore")]
+pub fn multi_miller_loop_parallel(terms: &[(&G1Affine, &G2Prepared)]) -> MillerLoopOutput {
+    use rayon::prelude::*;
 
-    for &mut (_p, ref mut coeffs) in &mut pairs {
-        assert_eq!(coeffs.next(), None);
+    let num_threads = rayon::current_num_threads();
+    if terms.len() <= 1 || num_threads == 1 {
+        return multi_miller_loop(terms);
     }
 
-    Gt(f)
+    let chunk = terms.len().div_ceil(num_threads);
+    terms
+        .par_chunks(chunk)
+        .map(multi_miller_loop)
+        .reduce(MillerLoopOutput::one, |a, b| a * b)
 }
 
+#[cfg(feature = "alloc")]
 pub fn pairing(g1: &G1Affine, g2: &G2Affine) -> Gt {
     let g2 = G2Prepared::from_affine(*g2);
     let terms: &[(&G1Affine, &G2Prepared)] = &[(g1, &g2)];
@@ -627,6 +802,24 @@ pub fn pairing(g1: &G1Affine, g2: &G2Affine) -> Gt {
 #[derive(Clone, Debug)]
 pub struct Bn256;
 
+impl BnParameters for Bn256 {
+    type Fq = Fq;
+    type Fq2 = Fq2;
+    type Fq12 = Fq12;
+    type G1Affine = G1Affine;
+    type G2Affine = G2Affine;
+
+    const X: &'static [u64] = &[BN_X];
+    const X_IS_NEGATIVE: bool = false;
+    const ATE_LOOP_COUNT: &'static [i8] = &SIX_U_PLUS_2_NAF;
+    const TWIST_TYPE: TwistType = TwistType::D;
+
+    const TWIST_MUL_BY_Q_X: Fq2 = FROBENIUS_COEFF_FQ6_C1[1];
+    const TWIST_MUL_BY_Q_Y: Fq2 = XI_TO_Q_MINUS_1_OVER_2;
+    const TWIST_MUL_BY_Q2_X: Fq2 = FROBENIUS_COEFF_FQ6_C1[2];
+}
+
+#[cfg(feature = "alloc")]
 impl Engine for Bn256 {
     type Scalar = Fr;
     type G1 = G1;
@@ -640,20 +833,70 @@ impl Engine for Bn256 {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl MultiMillerLoop for Bn256 {
     type G2Prepared = G2Prepared;
-    type Result = Gt;
+    type Result = MillerLoopOutput;
 
     fn multi_miller_loop(terms: &[(&Self::G1Affine, &Self::G2Prepared)]) -> Self::Result {
         multi_miller_loop(terms)
     }
 }
 
+#[cfg(feature = "alloc")]
+impl Bn256 {
+    /// Returns whether $\prod_i e(P_i, Q_i)$ is the identity for the `terms`
+    /// $(P_i, Q_i)$, using a single Miller loop and final exponentiation.
+    pub fn pairing_product_is_identity(terms: &[(&G1Affine, &G2Prepared)]) -> bool {
+        bool::from(
+            multi_miller_loop(terms)
+                .final_exponentiation()
+                .is_identity(),
+        )
+    }
+
+    /// Returns whether every one of the `equations`, each a list of terms
+    /// checked as in [`Bn256::pairing_product_is_identity`], holds.
+    ///
+    /// Every equation but the first is raised to a random power $\rho_j$ drawn
+    /// from `rng` by multiplying its $P_i$ by $\rho_j$, after which the
+    /// products are combined into a single Miller loop and final
+    /// exponentiation. If any equation does not hold, the combination is the
+    /// identity with probability $1/r$.
+    pub fn batch_pairing_product_is_identity(
+        equations: &[&[(&G1Affine, &G2Prepared)]],
+        mut rng: impl RngCore,
+    ) -> bool {
+        let mut points = vec![];
+        let mut prepared = vec![];
+        for (j, terms) in equations.iter().enumerate() {
+            let rho = if j == 0 {
+                Fr::one()
+            } else {
+                Fr::random(&mut rng)
+            };
+            for &(p, q) in terms.iter() {
+                points.push(p * rho);
+                prepared.push(q);
+            }
+        }
+
+        let mut affine = vec![G1Affine::identity(); points.len()];
+        G1::batch_normalize(&points, &mut affine);
+
+        let terms: Vec<_> = affine.iter().zip(prepared).collect();
+        Bn256::pairing_product_is_identity(&terms)
+    }
+}
+
+#[cfg(test)]
+use ff::PrimeField;
 #[cfg(test)]
 use rand::SeedableRng;
 #[cfg(test)]
 use rand_xorshift::XorShiftRng;
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_pairing() {
     let g1 = G1::generator();
@@ -709,6 +952,7 @@ fn test_pairing() {
     }
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn random_bilinearity_tests() {
     let mut rng = XorShiftRng::from_seed([
@@ -757,6 +1001,7 @@ fn random_bilinearity_tests() {
     }
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 pub fn engine_tests() {
     let mut rng = XorShiftRng::from_seed([
@@ -803,6 +1048,7 @@ pub fn engine_tests() {
     }
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn random_miller_loop_tests() {
     let mut rng = XorShiftRng::from_seed([
@@ -831,3 +1077,781 @@ fn random_miller_loop_tests() {
         assert_eq!(abcd, abcd_with_double_loop);
     }
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_miller_loop_output_mul() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let terms: Vec<(G1Affine, G2Prepared)> = (0..4)
+        .map(|_| {
+            let p = G1Affine::from(G1::random(&mut rng));
+            let q = G2Affine::from(G2::random(&mut rng));
+            (p, G2Prepared::from(q))
+        })
+        .collect();
+    let terms: Vec<(&G1Affine, &G2Prepared)> = terms.iter().map(|(p, q)| (p, q)).collect();
+
+    let expected = multi_miller_loop(&terms).final_exponentiation();
+
+    // Partial loops combine by multiplication before the final exponentiation.
+    let mut f = multi_miller_loop(&terms[..1]);
+    f *= multi_miller_loop(&terms[1..3]);
+    let f = f * multi_miller_loop(&terms[3..]);
+    assert_eq!(f.final_exponentiation(), expected);
+
+    let f: MillerLoopOutput = terms
+        .iter()
+        .map(|term| multi_miller_loop(&[*term]))
+        .product();
+    assert_eq!(f.final_exponentiation(), expected);
+
+    assert_eq!(
+        MillerLoopOutput::one().final_exponentiation(),
+        Gt::identity()
+    );
+    assert_eq!(
+        multi_miller_loop(&[]).final_exponentiation(),
+        Gt::identity()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_pairing_product_is_identity() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // e(aP, Q) * e(-P, aQ) == 1 holds, e(aP, Q) * e(-P, Q) == 1 does not.
+    let mut equations = vec![];
+    for _ in 0..5 {
+        let a = Fr::random(&mut rng);
+        let p = G1::random(&mut rng);
+        let q = G2::random(&mut rng);
+        let valid = [
+            (G1Affine::from(p * a), G2Prepared::from(G2Affine::from(q))),
+            (G1Affine::from(-p), G2Prepared::from(G2Affine::from(q * a))),
+        ];
+        let invalid = [
+            (G1Affine::from(p * a), G2Prepared::from(G2Affine::from(q))),
+            (G1Affine::from(-p), G2Prepared::from(G2Affine::from(q))),
+        ];
+        equations.push((valid, invalid));
+    }
+
+    fn terms(pairs: &[(G1Affine, G2Prepared)]) -> Vec<(&G1Affine, &G2Prepared)> {
+        pairs.iter().map(|(p, q)| (p, q)).collect()
+    }
+    let valid: Vec<_> = equations.iter().map(|(valid, _)| terms(valid)).collect();
+    let invalid: Vec<_> = equations
+        .iter()
+        .map(|(_, invalid)| terms(invalid))
+        .collect();
+
+    for (valid, invalid) in valid.iter().zip(invalid.iter()) {
+        assert!(Bn256::pairing_product_is_identity(valid));
+        assert!(!Bn256::pairing_product_is_identity(invalid));
+    }
+    assert!(Bn256::pairing_product_is_identity(&[]));
+
+    let batch: Vec<&[_]> = valid.iter().map(|terms| &terms[..]).collect();
+    assert!(Bn256::batch_pairing_product_is_identity(&batch, &mut rng));
+    assert!(Bn256::batch_pairing_product_is_identity(&[], &mut rng));
+
+    // A single invalid equation in any position fails the batch.
+    for i in 0..batch.len() {
+        let mut batch = batch.clone();
+        batch[i] = &invalid[i];
+        assert!(!Bn256::batch_pairing_product_is_identity(&batch, &mut rng));
+    }
+
+    // Two invalid equations do not cancel each other out: e(P, Q) and
+    // e(-P, Q) each fail while their product holds.
+    let p = G1Affine::from(G1::random(&mut rng));
+    let q = G2Prepared::from(G2Affine::from(G2::random(&mut rng)));
+    let minus_p = -p;
+    let batch: [&[_]; 2] = [&[(&p, &q)], &[(&minus_p, &q)]];
+    assert!(Bn256::pairing_product_is_identity(&[
+        (&p, &q),
+        (&minus_p, &q)
+    ]));
+    assert!(!Bn256::batch_pairing_product_is_identity(&batch, &mut rng));
+}
+
+#[cfg(feature = "multicore")]
+#[test]
+fn test_multi_miller_loop_parallel() {
+    use ark_std::{end_timer, start_timer};
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let pairs: Vec<(G1Affine, G2Prepared)> = (0..128)
+        .map(|_| {
+            let p = G1Affine::from(G1::random(&mut rng));
+            let q = G2Affine::from(G2::random(&mut rng));
+            (p, G2Prepared::from(q))
+        })
+        .collect();
+    let terms: Vec<(&G1Affine, &G2Prepared)> = pairs.iter().map(|(p, q)| (p, q)).collect();
+
+    // Run in a pool of a fixed size so that the terms are split into chunks
+    // regardless of the number of cores.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    for &n in [0, 1, 2, 3, 17, 128].iter() {
+        let terms = &terms[..n];
+        assert_eq!(
+            pool.install(|| multi_miller_loop_parallel(terms))
+                .final_exponentiation(),
+            multi_miller_loop(terms).final_exponentiation()
+        );
+    }
+
+    let message = format!("multi_miller_loop of {} pairs", terms.len());
+    let start = start_timer!(|| message);
+    let serial = multi_miller_loop(&terms);
+    end_timer!(start);
+
+    let message = format!("multi_miller_loop_parallel of {} pairs", terms.len());
+    let start = start_timer!(|| message);
+    let parallel = multi_miller_loop_parallel(&terms);
+    end_timer!(start);
+
+    assert_eq!(
+        parallel.final_exponentiation(),
+        serial.final_exponentiation()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_multi_miller_loop_affine() {
+    use ark_std::{end_timer, start_timer};
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let mut points: Vec<(G1Affine, G2Affine)> = (0..64)
+        .map(|_| {
+            (
+                G1Affine::from(G1::random(&mut rng)),
+                G2Affine::from(G2::random(&mut rng)),
+            )
+        })
+        .collect();
+    points[1].0 = G1Affine::identity();
+    points[2].1 = G2Affine::identity();
+
+    for &n in [0, 1, 2, 3, 4, 17, 64].iter() {
+        let points = &points[..n];
+        let prepared: Vec<_> = points
+            .iter()
+            .map(|(p, q)| (*p, G2Prepared::from(*q)))
+            .collect();
+        let prepared: Vec<_> = prepared.iter().map(|(p, q)| (p, q)).collect();
+        let affine: Vec<_> = points.iter().map(|(p, q)| (p, q)).collect();
+
+        assert_eq!(
+            multi_miller_loop_affine(&affine).final_exponentiation(),
+            multi_miller_loop(&prepared).final_exponentiation()
+        );
+    }
+
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    assert_eq!(
+        multi_miller_loop_affine(&[(&g1, &g2)]).final_exponentiation(),
+        Gt::generator()
+    );
+
+    for &n in [1, 8, 64].iter() {
+        let points = &points[..n];
+
+        let message = format!("prepared multi_miller_loop of {} pairs", n);
+        let start = start_timer!(|| message);
+        let prepared: Vec<_> = points
+            .iter()
+            .map(|(p, q)| (*p, G2Prepared::from(*q)))
+            .collect();
+        let prepared: Vec<_> = prepared.iter().map(|(p, q)| (p, q)).collect();
+        let expected = multi_miller_loop(&prepared);
+        end_timer!(start);
+
+        let message = format!("multi_miller_loop_affine of {} pairs", n);
+        let start = start_timer!(|| message);
+        let affine: Vec<_> = points.iter().map(|(p, q)| (p, q)).collect();
+        let f = multi_miller_loop_affine(&affine);
+        end_timer!(start);
+
+        assert_eq!(f.final_exponentiation(), expected.final_exponentiation());
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_multi_miller_loop_g1_prepared() {
+    use ark_std::{end_timer, start_timer};
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let mut points: Vec<(G1Affine, G2Affine)> = (0..64)
+        .map(|_| {
+            (
+                G1Affine::from(G1::random(&mut rng)),
+                G2Affine::from(G2::random(&mut rng)),
+            )
+        })
+        .collect();
+    points[1].0 = G1Affine::identity();
+    points[2].1 = G2Affine::identity();
+    let prepared: Vec<_> = points
+        .iter()
+        .map(|(p, q)| (G1Prepared::from(*p), *q))
+        .collect();
+
+    assert!(prepared[1].0.is_zero());
+    assert!(!prepared[0].0.is_zero());
+
+    let affine: Vec<_> = points.iter().map(|(p, q)| (p, q)).collect();
+    let prepared: Vec<_> = prepared.iter().map(|(p, q)| (p, q)).collect();
+
+    for &n in [0, 1, 2, 3, 4, 17, 64].iter() {
+        assert_eq!(
+            multi_miller_loop_g1_prepared(&prepared[..n]).final_exponentiation(),
+            multi_miller_loop_affine(&affine[..n]).final_exponentiation()
+        );
+    }
+
+    let g1 = G1Prepared::from(G1Affine::generator());
+    let g2 = G2Affine::generator();
+    assert_eq!(
+        multi_miller_loop_g1_prepared(&[(&g1, &g2)]).final_exponentiation(),
+        Gt::generator()
+    );
+
+    let message = format!("multi_miller_loop_affine of {} pairs", affine.len());
+    let start = start_timer!(|| message);
+    let expected = multi_miller_loop_affine(&affine);
+    end_timer!(start);
+
+    let message = format!("multi_miller_loop_g1_prepared of {} pairs", prepared.len());
+    let start = start_timer!(|| message);
+    let f = multi_miller_loop_g1_prepared(&prepared);
+    end_timer!(start);
+
+    assert_eq!(f.final_exponentiation(), expected.final_exponentiation());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_g2_prepared_bytes() {
+    use ark_std::{end_timer, start_timer};
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let p = G1Affine::from(G1::random(&mut rng));
+    for q in [
+        G2Affine::generator(),
+        G2Affine::identity(),
+        G2Affine::from(G2::random(&mut rng)),
+    ] {
+        let prepared = G2Prepared::from_affine(q);
+        let bytes = prepared.to_bytes();
+        assert_eq!(
+            bytes.len(),
+            if prepared.is_zero() {
+                160
+            } else {
+                G2_PREPARED_BYTES
+            }
+        );
+
+        let back = G2Prepared::from_bytes(&bytes).unwrap();
+        assert_eq!(back.coeffs, prepared.coeffs);
+        assert_eq!(back.is_zero(), prepared.is_zero());
+        assert_eq!(back.point(), q);
+        assert_eq!(
+            multi_miller_loop(&[(&p, &back)]).final_exponentiation(),
+            pairing(&p, &q)
+        );
+    }
+
+    let bytes = G2Prepared::from_affine(G2Affine::generator()).to_bytes();
+
+    // Wrong lengths.
+    assert!(G2Prepared::from_bytes(&[]).is_none());
+    assert!(G2Prepared::from_bytes(&bytes[..bytes.len() - 192]).is_none());
+    assert!(G2Prepared::from_bytes(&bytes[..160]).is_none());
+    let mut longer = bytes.clone();
+    longer.extend_from_slice(&[0u8; 192]);
+    assert!(G2Prepared::from_bytes(&longer).is_none());
+
+    // A flipped bit anywhere fails the checksum.
+    for i in [0, 127, 128, 159, 160, 1000, bytes.len() - 1] {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 1;
+        assert!(G2Prepared::from_bytes(&corrupted).is_none());
+    }
+
+    // The identity with coefficients, and another point without, are
+    // rejected even with a valid checksum.
+    let identity = G2Prepared::from_affine(G2Affine::identity());
+    let mut swapped = identity.clone();
+    swapped.coeffs = G2Prepared::from_affine(G2Affine::generator()).coeffs;
+    assert!(G2Prepared::from_bytes(&swapped.to_bytes()).is_none());
+    let mut swapped = G2Prepared::from_affine(G2Affine::generator());
+    swapped.coeffs = identity.coeffs;
+    assert!(G2Prepared::from_bytes(&swapped.to_bytes()).is_none());
+
+    let n = 100;
+    let q = G2Affine::from(G2::random(&mut rng));
+
+    let message = format!("G2Prepared::from_affine, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert!(!G2Prepared::from_affine(q).is_zero());
+    }
+    end_timer!(start);
+
+    let bytes = G2Prepared::from_affine(q).to_bytes();
+    let message = format!("G2Prepared::from_bytes, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert!(!G2Prepared::from_bytes(&bytes).unwrap().is_zero());
+    }
+    end_timer!(start);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_g2_prepared_forged_bytes() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let p = G1Affine::generator();
+
+    // Zeroed coefficients with a recomputed checksum are rejected, and
+    // would fail rather than pass a pairing check if used anyway.
+    let mut forged = G2Prepared::from_affine(G2Affine::generator());
+    for c in forged.coeffs.iter_mut() {
+        *c = (Fq2::zero(), Fq2::zero(), Fq2::zero());
+    }
+    assert!(G2Prepared::from_bytes(&forged.to_bytes()).is_none());
+    let f = multi_miller_loop(&[(&p, &forged)]);
+    assert_eq!(f.0, Fq12::zero());
+    assert!(!bool::from(f.final_exponentiation().is_identity()));
+    assert!(!Bn256::pairing_product_is_identity(&[(&p, &forged)]));
+
+    // The coefficients of another point are rejected.
+    let mut forged = G2Prepared::from_affine(G2Affine::generator());
+    forged.coeffs = G2Prepared::from_affine(G2Affine::from(G2::random(&mut rng))).coeffs;
+    assert!(G2Prepared::from_bytes(&forged.to_bytes()).is_none());
+
+    // Points off the curve or outside the subgroup are rejected even with
+    // their own coefficients.
+    let mut repr = G2Affine::generator().to_uncompressed();
+    repr.as_mut()[127] ^= 1;
+    let off_curve = G2Affine::from_uncompressed_unchecked(&repr).unwrap();
+    assert!(!bool::from(crate::arithmetic::CurveAffine::is_on_curve(
+        &off_curve
+    )));
+    let outside = G2Affine::random(&mut rng);
+    assert!(!bool::from(
+        group::cofactor::CofactorGroup::is_torsion_free(&outside.to_curve())
+    ));
+    for q in [off_curve, outside] {
+        let bytes = G2Prepared::from_affine(q).to_bytes();
+        assert!(G2Prepared::from_bytes(&bytes).is_none());
+    }
+}
+
+#[test]
+fn test_bn_parameters() {
+    // The NAF digits add up to 6x + 2.
+    let naf = Bn256::ATE_LOOP_COUNT
+        .iter()
+        .rev()
+        .fold(0i128, |acc, &digit| 2 * acc + digit as i128);
+    assert_eq!(naf, 6 * BN_X as i128 + 2);
+
+    // (q - 1) / d as little endian limbs
+    let q_minus_1_over = |d: u64| {
+        let mut limbs = crate::bn256::fq::MODULUS.0;
+        limbs[0] -= 1;
+        let mut rem = 0u128;
+        for limb in limbs.iter_mut().rev() {
+            let cur = (rem << 64) | *limb as u128;
+            *limb = (cur / d as u128) as u64;
+            rem = cur % d as u128;
+        }
+        assert_eq!(rem, 0);
+        limbs
+    };
+
+    // 9 + u
+    let xi = Fq2 {
+        c0: Fq::from_raw([9, 0, 0, 0]),
+        c1: Fq::from_raw([1, 0, 0, 0]),
+    };
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for _ in 0..10 {
+        let a = Fq2::random(&mut rng);
+        let mut b = a;
+        b.mul_by_nonresidue();
+        assert_eq!(a * xi, b);
+    }
+
+    let q_x = xi.pow_vartime(q_minus_1_over(3));
+    assert_eq!(Bn256::TWIST_MUL_BY_Q_X, q_x);
+    assert_eq!(Bn256::TWIST_MUL_BY_Q_Y, xi.pow_vartime(q_minus_1_over(2)));
+
+    // xi^((q^2 - 1) / 3) = xi^((q - 1) / 3 * q) * xi^((q - 1) / 3)
+    let mut q2_x = q_x;
+    q2_x.conjugate();
+    assert_eq!(Bn256::TWIST_MUL_BY_Q2_X, q2_x * q_x);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_gt_generator() {
+    assert_eq!(
+        Gt::generator(),
+        pairing(&G1Affine::generator(), &G2Affine::generator())
+    );
+    assert!(!bool::from(Gt::generator().is_identity()));
+
+    // g^r == 1
+    assert_eq!(
+        Gt::generator().0.pow_vartime(crate::bn256::fr::MODULUS.0),
+        Fq12::one()
+    );
+}
+
+#[test]
+fn test_gt_random() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..10 {
+        let a = Gt::random(&mut rng);
+        let b = Gt::random(&mut rng);
+        assert!(a != b);
+        assert_eq!(a.0.pow_vartime(crate::bn256::fr::MODULUS.0), Fq12::one());
+    }
+}
+
+#[test]
+fn test_gt_bytes() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let in_gt = |f: &Fq12| f.pow_vartime(crate::bn256::fr::MODULUS.0) == Fq12::one();
+
+    for _ in 0..10 {
+        let a = Gt::random(&mut rng);
+        assert_eq!(Gt::from_bytes(&a.to_bytes()).unwrap(), a);
+
+        // An arbitrary element of Fq12 is not in Gt.
+        let f = Fq12::random(&mut rng);
+        assert!(!in_gt(&f));
+        assert!(bool::from(Gt::from_bytes(&f.to_bytes()).is_none()));
+
+        // f^((q^6 - 1)(q^2 + 1)) is in the cyclotomic subgroup but its order
+        // is not r.
+        let mut g = f;
+        g.conjugate();
+        g.mul_assign(&f.invert().unwrap());
+        let mut h = g;
+        h.frobenius_map(2);
+        g.mul_assign(&h);
+        assert!(!in_gt(&g));
+        assert!(bool::from(Gt::from_bytes(&g.to_bytes()).is_none()));
+
+        // The full final exponentiation lands in Gt.
+        let g = MillerLoopOutput(f).final_exponentiation();
+        assert!(in_gt(&g.0));
+        assert_eq!(Gt::from_bytes(&g.to_bytes()).unwrap(), g);
+    }
+
+    assert_eq!(
+        Gt::from_bytes(&Gt::identity().to_bytes()).unwrap(),
+        Gt::identity()
+    );
+    assert!(bool::from(Gt::from_bytes(&[0u8; 384]).is_none()));
+
+    // A coefficient equal to the modulus is rejected.
+    let mut bytes = Gt::generator().to_bytes();
+    bytes[..32].copy_from_slice(&[
+        0x47, 0xfd, 0x7c, 0xd8, 0x16, 0x8c, 0x20, 0x3c, 0x8d, 0xca, 0x71, 0x68, 0x91, 0x6a, 0x81,
+        0x97, 0x5d, 0x58, 0x81, 0x81, 0xb6, 0x45, 0x50, 0xb8, 0x29, 0xa0, 0x31, 0xe1, 0x72, 0x4e,
+        0x64, 0x30,
+    ]);
+    assert!(bool::from(Gt::from_bytes(&bytes).is_none()));
+}
+
+#[test]
+fn test_gt_compressed() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for a in [Gt::identity(), Gt::generator(), -Gt::generator()] {
+        assert_eq!(Gt::from_compressed(&a.to_compressed()).unwrap(), a);
+    }
+    assert_eq!(Gt::identity().to_compressed(), [0u8; 192]);
+
+    for _ in 0..100 {
+        let a = Gt::random(&mut rng);
+        assert_eq!(Gt::from_compressed(&a.to_compressed()).unwrap(), a);
+
+        // Points of the torus outside of Gt are rejected.
+        let g = Fq6::random(&mut rng);
+        assert!(bool::from(Gt::from_compressed(&g.to_bytes()).is_none()));
+    }
+
+    // Non-canonical encodings are rejected.
+    let mut bytes = Gt::generator().to_compressed();
+    bytes[..32].fill(0xff);
+    assert!(bool::from(Gt::from_compressed(&bytes).is_none()));
+}
+
+#[test]
+fn test_gt_pow_vartime() {
+    use ark_std::{end_timer, start_timer};
+    use core::convert::TryInto;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..20 {
+        let a = Gt::random(&mut rng);
+        let s = Fr::random(&mut rng);
+        let repr = s.to_repr();
+        let limbs: Vec<u64> = repr
+            .chunks(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(a.pow_vartime(limbs), a * s);
+    }
+    assert_eq!(
+        Gt::generator().pow_vartime(crate::bn256::fr::MODULUS.0),
+        Gt::identity()
+    );
+    assert_eq!(Gt::generator().pow_vartime([]), Gt::identity());
+
+    // Compare against the constant time multiplication by a scalar.
+    let n = 100;
+    let a = Gt::random(&mut rng);
+    let s = Fr::random(&mut rng);
+    let limbs: Vec<u64> = s
+        .to_repr()
+        .chunks(8)
+        .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+        .collect();
+
+    let message = format!("Gt multiplication by a scalar, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert_eq!(a * s, a * s);
+    }
+    end_timer!(start);
+
+    let message = format!("Gt pow_vartime, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert_eq!(a.pow_vartime(&limbs), a.pow_vartime(&limbs));
+    }
+    end_timer!(start);
+}
+
+#[test]
+fn test_gt_mul_scalar() {
+    use ark_std::{end_timer, start_timer};
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // Plain double-and-add over the bits of the scalar.
+    let ladder = |a: &Gt, s: &Fr| {
+        let mut acc = Gt::identity();
+        for bit in s
+            .to_repr()
+            .iter()
+            .rev()
+            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
+        {
+            acc = acc.double();
+            acc = Gt::conditional_select(&acc, &(acc + a), bit);
+        }
+        acc
+    };
+
+    for _ in 0..20 {
+        let a = Gt::random(&mut rng);
+        let s = Fr::random(&mut rng);
+        let expected = ladder(&a, &s);
+        assert_eq!(a * s, expected);
+        assert_eq!(a.mul_vartime(&s), expected);
+    }
+
+    let a = Gt::random(&mut rng);
+    for s in [Fr::zero(), Fr::one(), -Fr::one(), Fr::from(2), -Fr::from(2)] {
+        let expected = ladder(&a, &s);
+        assert_eq!(a * s, expected);
+        assert_eq!(a.mul_vartime(&s), expected);
+    }
+    assert_eq!(Gt::identity() * Fr::random(&mut rng), Gt::identity());
+    assert_eq!(
+        Gt::identity().mul_vartime(&Fr::random(&mut rng)),
+        Gt::identity()
+    );
+
+    let n = 100;
+    let s = Fr::random(&mut rng);
+
+    let message = format!("Gt double-and-add, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert_eq!(ladder(&a, &s), ladder(&a, &s));
+    }
+    end_timer!(start);
+
+    let message = format!("Gt multiplication by a scalar, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert_eq!(a * s, a * s);
+    }
+    end_timer!(start);
+
+    let message = format!("Gt mul_vartime, {} times", n);
+    let start = start_timer!(|| message);
+    for _ in 0..n {
+        assert_eq!(a.mul_vartime(&s), a.mul_vartime(&s));
+    }
+    end_timer!(start);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_gt_multiexp() {
+    use ark_std::{end_timer, start_timer};
+    use core::convert::TryInto;
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // Exponentiation of the underlying Fq12 by the canonical scalar, which
+    // does not go through the GLS split or the cyclotomic squarings.
+    let pow = |base: &Gt, s: &Fr| {
+        let limbs: Vec<u64> = s
+            .to_repr()
+            .chunks(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        Gt(base.0.pow_vartime(&limbs))
+    };
+
+    assert_eq!(Gt::multiexp(&[], &[]), Gt::identity());
+
+    // Below, at and above MULTIEXP_THRESHOLD, with pairing outputs as bases.
+    for n in [
+        1,
+        MULTIEXP_THRESHOLD - 1,
+        MULTIEXP_THRESHOLD,
+        MULTIEXP_THRESHOLD + 1,
+        33,
+    ] {
+        let a: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+        let scalars: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+        let bases: Vec<Gt> = a
+            .iter()
+            .map(|a| pairing(&G1Affine::from(G1::generator() * a), &G2Affine::generator()))
+            .collect();
+
+        let expected: Gt = bases
+            .iter()
+            .zip(scalars.iter())
+            .map(|(b, s)| pow(b, s))
+            .sum();
+        let result = Gt::multiexp(&bases, &scalars);
+        assert_eq!(result, expected, "n = {}", n);
+
+        // By bilinearity the product is e(sum_i a_i s_i G1, G2).
+        let exponent = a
+            .iter()
+            .zip(scalars.iter())
+            .fold(Fr::zero(), |acc, (a, s)| acc + a * s);
+        assert_eq!(result, Gt::generator() * exponent, "n = {}", n);
+    }
+
+    // Scalars whose GLS components are all zero or negative, on both paths.
+    for n in [MULTIEXP_THRESHOLD - 1, MULTIEXP_THRESHOLD + 1] {
+        let bases: Vec<Gt> = (0..n).map(|_| Gt::random(&mut rng)).collect();
+        let scalars: Vec<Fr> = (0..n)
+            .map(|i| match i % 3 {
+                0 => Fr::zero(),
+                1 => -Fr::one(),
+                _ => Fr::one(),
+            })
+            .collect();
+
+        let expected: Gt = bases
+            .iter()
+            .zip(scalars.iter())
+            .map(|(b, s)| pow(b, s))
+            .sum();
+        assert_eq!(Gt::multiexp(&bases, &scalars), expected, "n = {}", n);
+    }
+
+    for &n in [8, 16, 32, 64, 128].iter() {
+        let bases: Vec<Gt> = (0..n).map(|_| Gt::random(&mut rng)).collect();
+        let scalars: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+
+        let message = format!("Gt products of {} mul_vartime", n);
+        let start = start_timer!(|| message);
+        let expected: Gt = bases
+            .iter()
+            .zip(scalars.iter())
+            .map(|(b, s)| b.mul_vartime(s))
+            .sum();
+        end_timer!(start);
+
+        let message = format!("Gt multiexp of {} terms", n);
+        let start = start_timer!(|| message);
+        assert_eq!(Gt::multiexp(&bases, &scalars), expected);
+        end_timer!(start);
+    }
+}
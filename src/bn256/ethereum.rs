@@ -0,0 +1,390 @@
+//! This module implements the encodings used by the Ethereum precompiles for
+//! BN254 ([EIP-196](https://eips.ethereum.org/EIPS/eip-196) and
+//! [EIP-197](https://eips.ethereum.org/EIPS/eip-197)), along with `ecAdd`
+//! (0x06), `ecMul` (0x07) and `ecPairing` (0x08) on raw precompile input.
+//!
+//! Field elements are 32-byte big endian integers. A G1 point is encoded as
+//! `x || y` and a G2 point as `x.c1 || x.c0 || y.c1 || y.c0`, that is with the
+//! imaginary part first. The point at infinity is encoded as all zeros.
+
+use crate::arithmetic::{BaseExt, CurveAffine};
+use crate::bn256::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use core::convert::TryInto;
+use ff::{Field, PrimeField};
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Curve};
+use subtle::{Choice, ConditionallySelectable, CtOption};
+
+/// Size of an encoded field element.
+const FIELD_SIZE: usize = 32;
+
+/// Size of an encoded G1 point.
+pub const G1_SIZE: usize = 2 * FIELD_SIZE;
+
+/// Size of an encoded G2 point.
+pub const G2_SIZE: usize = 4 * FIELD_SIZE;
+
+/// Size of one (G1, G2) pair of `ecPairing` input.
+pub const PAIR_SIZE: usize = G1_SIZE + G2_SIZE;
+
+fn fq_to_be_bytes(a: &Fq, out: &mut [u8]) {
+    for (o, b) in out.iter_mut().zip(a.to_bytes().iter().rev()) {
+        *o = *b;
+    }
+}
+
+fn fq_from_be_bytes(bytes: &[u8]) -> CtOption<Fq> {
+    let mut repr = [0u8; FIELD_SIZE];
+    for (r, b) in repr.iter_mut().zip(bytes.iter().rev()) {
+        *r = *b;
+    }
+    Fq::from_bytes(&repr)
+}
+
+/// Encodes a scalar as a 32-byte big endian integer.
+pub fn encode_fr(s: &Fr) -> [u8; FIELD_SIZE] {
+    let mut out = s.to_repr();
+    out.reverse();
+    out
+}
+
+/// Decodes a 32-byte big endian integer into a scalar, failing if it is not
+/// smaller than the group order.
+pub fn decode_fr(bytes: &[u8; FIELD_SIZE]) -> CtOption<Fr> {
+    let mut repr = *bytes;
+    repr.reverse();
+    Fr::from_repr(repr)
+}
+
+/// Encodes a G1 point as `x || y`.
+pub fn encode_g1(p: &G1Affine) -> [u8; G1_SIZE] {
+    let mut out = [0u8; G1_SIZE];
+    // The identity has coordinates (0, 0).
+    fq_to_be_bytes(&p.x, &mut out[..FIELD_SIZE]);
+    fq_to_be_bytes(&p.y, &mut out[FIELD_SIZE..]);
+    out
+}
+
+/// Decodes `x || y` into a G1 point, failing if a coordinate is not smaller
+/// than the field modulus or if the point is not on the curve.
+pub fn decode_g1(bytes: &[u8; G1_SIZE]) -> CtOption<G1Affine> {
+    let x = fq_from_be_bytes(&bytes[..FIELD_SIZE]);
+    let y = fq_from_be_bytes(&bytes[FIELD_SIZE..]);
+
+    x.and_then(|x| {
+        y.and_then(|y| {
+            let is_identity = x.is_zero() & y.is_zero();
+            let p = G1Affine::from_xy(x, y);
+            CtOption::new(
+                G1Affine::conditional_select(
+                    &p.unwrap_or(G1Affine::identity()),
+                    &G1Affine::identity(),
+                    is_identity,
+                ),
+                p.is_some() | is_identity,
+            )
+        })
+    })
+}
+
+/// Encodes a G2 point as `x.c1 || x.c0 || y.c1 || y.c0`.
+pub fn encode_g2(p: &G2Affine) -> [u8; G2_SIZE] {
+    let mut out = [0u8; G2_SIZE];
+    // The identity has coordinates (0, 0).
+    fq_to_be_bytes(&p.x.c1, &mut out[..FIELD_SIZE]);
+    fq_to_be_bytes(&p.x.c0, &mut out[FIELD_SIZE..2 * FIELD_SIZE]);
+    fq_to_be_bytes(&p.y.c1, &mut out[2 * FIELD_SIZE..3 * FIELD_SIZE]);
+    fq_to_be_bytes(&p.y.c0, &mut out[3 * FIELD_SIZE..]);
+    out
+}
+
+/// Decodes `x.c1 || x.c0 || y.c1 || y.c0` into a G2 point, failing if a
+/// coordinate is not smaller than the field modulus, or if the point is not
+/// on the twist or not in the subgroup of order r.
+pub fn decode_g2(bytes: &[u8; G2_SIZE]) -> CtOption<G2Affine> {
+    let x_c1 = fq_from_be_bytes(&bytes[..FIELD_SIZE]);
+    let x_c0 = fq_from_be_bytes(&bytes[FIELD_SIZE..2 * FIELD_SIZE]);
+    let y_c1 = fq_from_be_bytes(&bytes[2 * FIELD_SIZE..3 * FIELD_SIZE]);
+    let y_c0 = fq_from_be_bytes(&bytes[3 * FIELD_SIZE..]);
+
+    let x = x_c0.and_then(|c0| x_c1.map(|c1| Fq2 { c0, c1 }));
+    let y = y_c0.and_then(|c0| y_c1.map(|c1| Fq2 { c0, c1 }));
+
+    x.and_then(|x| {
+        y.and_then(|y| {
+            let is_identity = x.is_zero() & y.is_zero();
+            let p = G2Affine::from_xy(x, y);
+            let is_valid = p.is_some()
+                & p.map(|p| p.to_curve().is_torsion_free())
+                    .unwrap_or(Choice::from(0));
+            CtOption::new(
+                G2Affine::conditional_select(
+                    &p.unwrap_or(G2Affine::identity()),
+                    &G2Affine::identity(),
+                    is_identity,
+                ),
+                is_valid | is_identity,
+            )
+        })
+    })
+}
+
+/// Copies `input` into a buffer of `N` bytes, truncating it or padding it on
+/// the right with zeros as the precompiles do.
+fn padded<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut out = [0u8; N];
+    let len = core::cmp::min(N, input.len());
+    out[..len].copy_from_slice(&input[..len]);
+    out
+}
+
+/// The `ecAdd` precompile: adds two G1 points given as `a || b`. Returns
+/// `None` when the precompile fails, that is on invalid points.
+pub fn ec_add(input: &[u8]) -> Option<[u8; G1_SIZE]> {
+    let input: [u8; 2 * G1_SIZE] = padded(input);
+    let a = decode_g1(&input[..G1_SIZE].try_into().unwrap());
+    let b = decode_g1(&input[G1_SIZE..].try_into().unwrap());
+
+    let sum = a.and_then(|a| b.map(|b| (a + b).to_affine()));
+    Option::from(sum).map(|p| encode_g1(&p))
+}
+
+/// The `ecMul` precompile: multiplies a G1 point by a scalar given as
+/// `p || s`. The scalar may be any 256-bit integer. Returns `None` when the
+/// precompile fails, that is on an invalid point.
+pub fn ec_mul(input: &[u8]) -> Option<[u8; G1_SIZE]> {
+    let input: [u8; G1_SIZE + FIELD_SIZE] = padded(input);
+    let p = decode_g1(&input[..G1_SIZE].try_into().unwrap());
+
+    // G1 has prime order r, so the scalar can be reduced modulo r.
+    let mut s = [0u8; 64];
+    for (s, b) in s.iter_mut().zip(input[G1_SIZE..].iter().rev()) {
+        *s = *b;
+    }
+    let s = Fr::from_bytes_wide(&s);
+
+    Option::from(p.map(|p| (p * s).to_affine())).map(|p| encode_g1(&p))
+}
+
+/// The `ecPairing` precompile: checks whether the product of the pairings of
+/// the `(G1, G2)` pairs in `input` is one, returning the result as a 32-byte
+/// big endian integer. Returns `None` when the precompile fails, that is on
+/// input whose length is not a multiple of 192 bytes or on invalid points.
+#[cfg(feature = "alloc")]
+pub fn ec_pairing(input: &[u8]) -> Option<[u8; FIELD_SIZE]> {
+    use crate::bn256::{Bn256, G2Prepared};
+    use alloc::vec::Vec;
+
+    if input.len() % PAIR_SIZE != 0 {
+        return None;
+    }
+
+    let mut pairs = Vec::with_capacity(input.len() / PAIR_SIZE);
+    for chunk in input.chunks(PAIR_SIZE) {
+        let g1 = Option::from(decode_g1(&chunk[..G1_SIZE].try_into().unwrap()))?;
+        let g2 = Option::from(decode_g2(&chunk[G1_SIZE..].try_into().unwrap()))?;
+        pairs.push((g1, G2Prepared::from_affine(g2)));
+    }
+
+    let terms: Vec<_> = pairs.iter().map(|(g1, g2)| (g1, g2)).collect();
+    let is_one = Bn256::pairing_product_is_identity(&terms);
+
+    let mut out = [0u8; FIELD_SIZE];
+    out[FIELD_SIZE - 1] = is_one as u8;
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::{G1, G2};
+    use group::Group;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn hex(s: &str) -> Vec<u8> {
+        let s: String = s.split_whitespace().collect();
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_encodings() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        // The generators in the precompile encoding.
+        let g1 = encode_g1(&G1Affine::generator());
+        assert_eq!(
+            &g1[..],
+            &hex("
+            0000000000000000000000000000000000000000000000000000000000000001
+            0000000000000000000000000000000000000000000000000000000000000002")[..]
+        );
+        let g2 = encode_g2(&G2Affine::generator());
+        assert_eq!(
+            &g2[..],
+            &hex("
+            198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+            1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+            090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+            12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa")[..]
+        );
+
+        assert_eq!(encode_g1(&G1Affine::identity()), [0u8; G1_SIZE]);
+        assert_eq!(encode_g2(&G2Affine::identity()), [0u8; G2_SIZE]);
+        assert!(bool::from(
+            decode_g1(&[0u8; G1_SIZE]).unwrap().is_identity()
+        ));
+        assert!(bool::from(
+            decode_g2(&[0u8; G2_SIZE]).unwrap().is_identity()
+        ));
+
+        for _ in 0..10 {
+            let s = Fr::random(&mut rng);
+            assert_eq!(decode_fr(&encode_fr(&s)).unwrap(), s);
+
+            let p = G1::random(&mut rng).to_affine();
+            assert_eq!(decode_g1(&encode_g1(&p)).unwrap(), p);
+
+            let q = G2::random(&mut rng).to_affine();
+            assert_eq!(decode_g2(&encode_g2(&q)).unwrap(), q);
+        }
+
+        // Scalars and coordinates must be canonical.
+        let modulus = hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+        assert!(bool::from(
+            decode_fr(&modulus[..].try_into().unwrap()).is_none()
+        ));
+        let mut p = g1;
+        p[..FIELD_SIZE].copy_from_slice(&hex(
+            "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd48",
+        ));
+        assert!(bool::from(decode_g1(&p).is_none()));
+
+        // Points must be on the curve.
+        let mut p = g1;
+        p[G1_SIZE - 1] = 3;
+        assert!(bool::from(decode_g1(&p).is_none()));
+        let mut q = g2;
+        q[G2_SIZE - 1] ^= 1;
+        assert!(bool::from(decode_g2(&q).is_none()));
+
+        // G2 points must be in the subgroup of order r.
+        let q = <G2 as Group>::random(&mut rng);
+        assert!(!bool::from(q.is_torsion_free()));
+        assert!(bool::from(decode_g2(&encode_g2(&q.to_affine())).is_none()));
+    }
+
+    #[test]
+    fn test_ec_add() {
+        // Vector from the go-ethereum precompile tests.
+        let input = hex("
+            18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9
+            063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266
+            07c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed
+            06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7");
+        let expected = hex("
+            2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703
+            301d1d33be6da8e509df21cc35964723180eed7532537db9ae5e7d48f195c915");
+        assert_eq!(&ec_add(&input).unwrap()[..], &expected[..]);
+
+        // Missing input is padded with zeros, i.e. the point at infinity.
+        assert_eq!(ec_add(&[]).unwrap(), [0u8; G1_SIZE]);
+        assert_eq!(&ec_add(&input[..G1_SIZE]).unwrap()[..], &input[..G1_SIZE]);
+
+        // Points that are not on the curve make the precompile fail.
+        let mut input = input;
+        input[G1_SIZE - 1] ^= 1;
+        assert!(ec_add(&input).is_none());
+    }
+
+    #[test]
+    fn test_ec_mul() {
+        // Vector from the go-ethereum precompile tests.
+        let input = hex("
+            2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb7
+            21611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb204
+            00000000000000000000000000000000000000000000000011138ce750fa15c2");
+        let expected = hex("
+            070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c
+            031b8ce914eba3a9ffb989f9cdd5b0f01943074bf4f0f315690ec3cec6981afc");
+        assert_eq!(&ec_mul(&input).unwrap()[..], &expected[..]);
+
+        // Scalars are not required to be reduced: r + 2 acts as 2.
+        let mut input = [0u8; G1_SIZE + FIELD_SIZE];
+        input[..G1_SIZE].copy_from_slice(&encode_g1(&G1Affine::generator()));
+        input[G1_SIZE..].copy_from_slice(&hex(
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000003",
+        ));
+        let expected = encode_g1(&G1::generator().double().to_affine());
+        assert_eq!(ec_mul(&input).unwrap(), expected);
+
+        // The largest 256-bit scalar.
+        input[G1_SIZE..].copy_from_slice(&[0xff; FIELD_SIZE]);
+        let s = Fr::from_bytes_wide(&{
+            let mut s = [0u8; 64];
+            s[..FIELD_SIZE].copy_from_slice(&[0xff; FIELD_SIZE]);
+            s
+        });
+        let expected = encode_g1(&(G1::generator() * s).to_affine());
+        assert_eq!(ec_mul(&input).unwrap(), expected);
+
+        input[G1_SIZE - 1] ^= 1;
+        assert!(ec_mul(&input).is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_ec_pairing() {
+        // Vector from the go-ethereum precompile tests.
+        let input = hex("
+            1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59
+            3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41
+            209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7
+            04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678
+            2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d
+            120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550
+            111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c
+            2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411
+            198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2
+            1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed
+            090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b
+            12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa");
+
+        let mut one = [0u8; FIELD_SIZE];
+        one[FIELD_SIZE - 1] = 1;
+        assert_eq!(ec_pairing(&input).unwrap(), one);
+
+        // Only the first pair.
+        assert_eq!(ec_pairing(&input[..PAIR_SIZE]).unwrap(), [0u8; FIELD_SIZE]);
+
+        // The empty product is one.
+        assert_eq!(ec_pairing(&[]).unwrap(), one);
+
+        // e(P, Q) * e(-P, Q) = 1
+        let p = G1Affine::generator();
+        let q = G2Affine::generator();
+        let mut input = Vec::new();
+        input.extend_from_slice(&encode_g1(&p));
+        input.extend_from_slice(&encode_g2(&q));
+        input.extend_from_slice(&encode_g1(&-p));
+        input.extend_from_slice(&encode_g2(&q));
+        assert_eq!(ec_pairing(&input).unwrap(), one);
+
+        // The input length must be a multiple of 192 bytes.
+        assert!(ec_pairing(&input[..PAIR_SIZE + 1]).is_none());
+
+        // G2 points outside of the subgroup make the precompile fail.
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let q = <G2 as Group>::random(&mut rng).to_affine();
+        input[G1_SIZE..PAIR_SIZE].copy_from_slice(&encode_g2(&q));
+        assert!(ec_pairing(&input).is_none());
+    }
+}
@@ -2,12 +2,15 @@
 use super::assembly::assembly_field;
 use super::common::common_field;
 use super::LegendreSymbol;
+#[cfg(feature = "std")]
+use crate::arithmetic::BaseExtStd;
 use crate::arithmetic::{adc, mac, sbb, BaseExt, FieldExt, Group};
 use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::PrimeField;
 use rand::RngCore;
+#[cfg(feature = "std")]
 use std::io::{self, Read, Write};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -63,7 +66,13 @@ const BASEEXT_MODULUS: &'static str =
 const TWO_INV: Fq = Fq::from_raw([0, 0, 0, 0]);
 const ROOT_OF_UNITY_INV: Fq = Fq::from_raw([0, 0, 0, 0]);
 const DELTA: Fq = Fq::from_raw([0, 0, 0, 0]);
-const ZETA: Fq = Fq::from_raw([0, 0, 0, 0]);
+// 0x30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd48
+const ZETA: Fq = Fq::from_raw([
+    0xe4bd44e5607cfd48,
+    0xc28f069fbb966e3d,
+    0x5e6dd9e7e0acccb0,
+    0x30644e72e131a029,
+]);
 
 impl_binops_additive!(Fq, Fq);
 impl_binops_multiplicative!(Fq, Fq);
@@ -281,6 +290,16 @@ use rand::SeedableRng;
 #[cfg(test)]
 use rand_xorshift::XorShiftRng;
 
+#[test]
+fn test_zeta() {
+    let a = Fq::ZETA;
+    assert!(a != Fq::one());
+    let b = a * a;
+    assert!(b != Fq::one());
+    let c = b * a;
+    assert!(c == Fq::one());
+}
+
 #[test]
 fn test_ser() {
     let mut rng = XorShiftRng::from_seed([
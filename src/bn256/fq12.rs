@@ -1,6 +1,8 @@
 use super::fq::Fq;
 use super::fq2::Fq2;
 use super::fq6::Fq6;
+use crate::arithmetic::BnFq12;
+use core::convert::TryInto;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
 use rand::RngCore;
@@ -76,6 +78,24 @@ impl_binops_additive!(Fq12, Fq12);
 impl_binops_multiplicative!(Fq12, Fq12);
 
 impl Fq12 {
+    /// Attempts to convert a little-endian byte representation of the
+    /// coefficients `c0`, `c1` into a `Fq12`, failing if any of them is not
+    /// canonical.
+    pub fn from_bytes(bytes: &[u8; 384]) -> CtOption<Fq12> {
+        let c0 = Fq6::from_bytes(bytes[0..192].try_into().unwrap());
+        let c1 = Fq6::from_bytes(bytes[192..384].try_into().unwrap());
+        c0.and_then(|c0| c1.map(|c1| Fq12 { c0, c1 }))
+    }
+
+    /// Converts an element of `Fq12` into a byte representation of its
+    /// coefficients `c0`, `c1` in little-endian byte order.
+    pub fn to_bytes(self) -> [u8; 384] {
+        let mut res = [0u8; 384];
+        res[0..192].copy_from_slice(&self.c0.to_bytes());
+        res[192..384].copy_from_slice(&self.c1.to_bytes());
+        res
+    }
+
     pub fn mul_assign(&mut self, other: &Self) {
         let t0 = self.c0 * other.c0;
         let mut t1 = self.c1 * other.c1;
@@ -206,6 +226,19 @@ impl Fq12 {
         self.c0 = t0 + t1;
     }
 
+    /// Multiplies by the sparse element `1 + (c3 + c4 * v) * w`, which saves
+    /// the multiplication by `c0` of [`Fq12::mul_by_034`] for lines that are
+    /// normalized to a constant coefficient of one.
+    pub fn mul_by_34(&mut self, c3: &Fq2, c4: &Fq2) {
+        let mut t0 = self.c0;
+        t0.mul_by_01(c3, c4);
+        let mut t1 = self.c1;
+        t1.mul_by_01(c3, c4);
+        t1.mul_by_nonresidue();
+        self.c0 += t1;
+        self.c1 += t0;
+    }
+
     pub fn invert(&self) -> CtOption<Self> {
         let mut c0s = self.c0;
         c0s.square_assign();
@@ -269,6 +302,178 @@ impl Fq12 {
         t2.double_assign();
         self.c0.c2 = t2 + t5;
     }
+
+    /// Squares an element of the cyclotomic subgroup kept in Karabina's
+    /// compressed form, see https://eprint.iacr.org/2010/542.pdf
+    ///
+    /// Writing the element as $\sum_i g_i w^i$, only $g_1, g_2, g_3, g_5$, that is
+    /// `c0.c1`, `c0.c2`, `c1.c0` and `c1.c2`, are used and updated; the other
+    /// two coefficients are meaningless until [`Fq12::batch_decompress`].
+    pub fn cyclotomic_square_compressed(&mut self) {
+        let (g1, g2, g3, g5) = (self.c0.c1, self.c0.c2, self.c1.c0, self.c1.c2);
+
+        let g1g1 = g1.square();
+        let g5g5 = g5.square();
+        let g2g2 = g2.square();
+        let g3g3 = g3.square();
+        // 2 * g1 * g5 and 2 * g2 * g3
+        let g1g5 = (g1 + g5).square() - g1g1 - g5g5;
+        let g2g3 = (g2 + g3).square() - g2g2 - g3g3;
+
+        // g2' = 3 * (g1^2 + xi * g5^2) - 2 * g2
+        let mut t = g5g5;
+        t.mul_by_nonresidue();
+        t += g1g1;
+        self.c0.c2 = (t - g2).double() + t;
+
+        // g1' = 3 * (g3^2 + xi * g2^2) - 2 * g1
+        let mut t = g2g2;
+        t.mul_by_nonresidue();
+        t += g3g3;
+        self.c0.c1 = (t - g1).double() + t;
+
+        // g3' = 3 * xi * 2 * g1 * g5 + 2 * g3
+        let mut t = g1g5;
+        t.mul_by_nonresidue();
+        self.c1.c0 = (t + g3).double() + t;
+
+        // g5' = 3 * 2 * g2 * g3 + 2 * g5
+        self.c1.c2 = (g2g3 + g5).double() + g2g3;
+    }
+
+    /// Recovers $g_0$ and $g_4$ of elements of the cyclotomic subgroup in
+    /// Karabina's compressed form, sharing a single inversion between them.
+    /// This does not branch on the elements.
+    pub fn batch_decompress(elements: &mut [Fq12]) {
+        // g4 = num / den with
+        //   num = xi * g5^2 + 3 * g1^2 - 2 * g2 and den = 4 * g3 if g3 != 0,
+        //   num = 2 * g1 * g5 and den = g2 otherwise,
+        // where g2 = g3 = 0 only for the identity, for which den = 1.
+        fn den(f: &Fq12) -> Fq2 {
+            let (g2, g3) = (f.c0.c2, f.c1.c0);
+            let den = Fq2::conditional_select(&g2, &Fq2::one(), g2.is_zero());
+            Fq2::conditional_select(&g3.double().double(), &den, g3.is_zero())
+        }
+
+        // Montgomery's trick: g0 holds the product of the preceding
+        // denominators and g4 the numerator until the inversion.
+        let mut acc = Fq2::one();
+        for f in elements.iter_mut() {
+            let (g1, g2, g3, g5) = (f.c0.c1, f.c0.c2, f.c1.c0, f.c1.c2);
+            let g1g1 = g1.square();
+            let mut num = g5.square();
+            num.mul_by_nonresidue();
+            let num = num + (g1g1 - g2).double() + g1g1;
+            let num = Fq2::conditional_select(&num, &(g1 * g5).double(), g3.is_zero());
+            f.c0.c0 = acc;
+            f.c1.c1 = num;
+            acc *= den(f);
+        }
+
+        let mut inv = acc.invert().unwrap();
+        for f in elements.iter_mut().rev() {
+            let g4 = f.c1.c1 * inv * f.c0.c0;
+            inv *= den(f);
+
+            let (g1, g2, g3, g5) = (f.c0.c1, f.c0.c2, f.c1.c0, f.c1.c2);
+            // g0 = xi * (2 * g4^2 + g3 * g5 - 3 * g1 * g2) + 1
+            let g1g2 = g1 * g2;
+            let mut g0 = (g4.square() - g1g2).double() - g1g2 + g3 * g5;
+            g0.mul_by_nonresidue();
+            g0 += Fq2::one();
+
+            f.c0.c0 = g0;
+            f.c1.c1 = g4;
+            *f = Fq12::conditional_select(f, &Fq12::one(), g2.is_zero() & g3.is_zero());
+        }
+    }
+
+    /// Raises an element of the cyclotomic subgroup to the power `exp`, given
+    /// as little-endian limbs, in time that depends on `exp` but not on this
+    /// element.
+    ///
+    /// The exponent is recoded in non-adjacent form, as inverting is a
+    /// conjugation in the cyclotomic subgroup. The powers $f^{2^i}$ are
+    /// computed with compressed squarings and the ones needed for the non-zero
+    /// digits are decompressed in batches.
+    pub fn cyclotomic_exp_vartime(&self, exp: &[u64]) -> Fq12 {
+        const BATCH: usize = 64;
+
+        let bit = |i: usize| exp.get(i / 64).map_or(0, |limb| (limb >> (i % 64)) & 1);
+        let bits = exp
+            .iter()
+            .rposition(|&limb| limb != 0)
+            .map_or(0, |i| 64 * (i + 1) - exp[i].leading_zeros() as usize);
+
+        let mut res: Option<Fq12> = None;
+        let mut powers = [Fq12::zero(); BATCH];
+        let mut negative = [false; BATCH];
+        let mut n = 0;
+        let mut sq = *self;
+        let mut carry = 0;
+        // The non-adjacent form is at most one digit longer than the exponent.
+        for i in 0..=bits {
+            if i > 0 {
+                sq.cyclotomic_square_compressed();
+            }
+            let digit = match bit(i) + carry {
+                1 if bit(i + 1) == 1 => {
+                    carry = 1;
+                    -1
+                }
+                1 => {
+                    carry = 0;
+                    1
+                }
+                v => {
+                    carry = v >> 1;
+                    0
+                }
+            };
+            if digit != 0 {
+                powers[n] = sq;
+                negative[n] = digit < 0;
+                n += 1;
+            }
+            if n == BATCH || (n > 0 && i == bits) {
+                Fq12::batch_decompress(&mut powers[..n]);
+                for (p, &negative) in powers[..n].iter_mut().zip(negative.iter()) {
+                    if negative {
+                        p.conjugate();
+                    }
+                    res = Some(res.map_or(*p, |res| res * *p));
+                }
+                n = 0;
+            }
+        }
+        res.unwrap_or_else(Fq12::one)
+    }
+}
+
+impl BnFq12<Fq2> for Fq12 {
+    fn mul_by_034(&mut self, c0: &Fq2, c3: &Fq2, c4: &Fq2) {
+        Fq12::mul_by_034(self, c0, c3, c4)
+    }
+
+    fn mul_by_014(&mut self, c0: &Fq2, c1: &Fq2, c4: &Fq2) {
+        Fq12::mul_by_014(self, c0, c1, c4)
+    }
+
+    fn conjugate(&mut self) {
+        Fq12::conjugate(self)
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        Fq12::frobenius_map(self, power)
+    }
+
+    fn cyclotomic_square(&mut self) {
+        Fq12::cyclotomic_square(self)
+    }
+
+    fn cyclotomic_exp_vartime(&self, exp: &[u64]) -> Fq12 {
+        Fq12::cyclotomic_exp_vartime(self, exp)
+    }
 }
 
 impl Field for Fq12 {
@@ -537,6 +742,26 @@ fn test_fq12_mul_by_034() {
     }
 }
 
+#[test]
+fn test_fq12_mul_by_34() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..1000 {
+        let c3 = Fq2::random(&mut rng);
+        let c4 = Fq2::random(&mut rng);
+        let mut a = Fq12::random(&mut rng);
+        let mut b = a;
+
+        a.mul_by_34(&c3, &c4);
+        b.mul_by_034(&Fq2::one(), &c3, &c4);
+
+        assert_eq!(a, b);
+    }
+}
+
 #[test]
 fn test_squaring() {
     let mut rng = XorShiftRng::from_seed([
@@ -584,3 +809,105 @@ fn test_frobenius() {
 fn test_field() {
     crate::tests::field::random_field_tests::<Fq12>("fq12".to_string());
 }
+
+#[test]
+fn test_fq12_bytes() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let a = Fq12::random(&mut rng);
+        let bytes = a.to_bytes();
+        assert_eq!(Fq12::from_bytes(&bytes).unwrap(), a);
+        assert_eq!(bytes[..64], a.c0.c0.to_bytes());
+        assert_eq!(bytes[320..], a.c1.c2.to_bytes());
+        assert_eq!(
+            Fq6::from_bytes(bytes[192..].try_into().unwrap()).unwrap(),
+            a.c1
+        );
+    }
+
+    // Every coefficient must be canonical.
+    for i in 0..12 {
+        let mut bytes = Fq12::one().to_bytes();
+        bytes[32 * i..32 * (i + 1)].fill(0xff);
+        assert!(bool::from(Fq12::from_bytes(&bytes).is_none()));
+    }
+}
+
+#[test]
+fn test_cyclotomic_exp() {
+    use ark_std::{end_timer, start_timer};
+
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // Maps a random element into the cyclotomic subgroup with f^((q^6 - 1)(q^2 + 1)).
+    let mut cyclotomic = || {
+        let f = Fq12::random(&mut rng);
+        let mut g = f;
+        g.conjugate();
+        g.mul_assign(&f.invert().unwrap());
+        let mut h = g;
+        h.frobenius_map(2);
+        g.mul_assign(&h);
+        g
+    };
+
+    // A single compressed squaring agrees with the cyclotomic squaring.
+    for _ in 0..100 {
+        let f = cyclotomic();
+        let mut a = f;
+        a.cyclotomic_square();
+        let mut b = f;
+        b.cyclotomic_square_compressed();
+        Fq12::batch_decompress(core::slice::from_mut(&mut b));
+        assert_eq!(a, b);
+    }
+
+    let mut exps = vec![vec![], vec![0], vec![1], vec![2], vec![0x44e992b44a6909f1]];
+    exps.push(vec![u64::MAX; 4]);
+    exps.push(vec![0, 0, 1 << 63, 0]);
+    for exp in exps.iter() {
+        let f = cyclotomic();
+        assert_eq!(f.cyclotomic_exp_vartime(exp), f.pow_vartime(exp));
+    }
+    let one = Fq12::one();
+    assert_eq!(one.cyclotomic_exp_vartime(&[u64::MAX; 4]), one);
+
+    // Compare against square-and-multiply with the cyclotomic squaring.
+    let n = 1000;
+    let x = [0x44e992b44a6909f1u64];
+    let elements: Vec<_> = (0..n).map(|_| cyclotomic()).collect();
+
+    let message = format!("exp_by_x with cyclotomic squarings, {} times", n);
+    let start = start_timer!(|| message);
+    let expected: Vec<_> = elements
+        .iter()
+        .map(|f| {
+            let mut res = Fq12::one();
+            for i in (0..64).rev() {
+                res.cyclotomic_square();
+                if (x[0] >> i) & 1 == 1 {
+                    res.mul_assign(f);
+                }
+            }
+            res
+        })
+        .collect();
+    end_timer!(start);
+
+    let message = format!("exp_by_x with compressed squarings, {} times", n);
+    let start = start_timer!(|| message);
+    let actual: Vec<_> = elements
+        .iter()
+        .map(|f| f.cyclotomic_exp_vartime(&x))
+        .collect();
+    end_timer!(start);
+
+    assert_eq!(expected, actual);
+}
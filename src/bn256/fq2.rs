@@ -1,11 +1,15 @@
 use super::fq::{Fq, NEGATIVE_ONE};
+use super::hash_to_curve::expand_message_xmd;
 use super::LegendreSymbol;
-use crate::arithmetic::BaseExt;
+#[cfg(feature = "std")]
+use crate::arithmetic::BaseExtStd;
+use crate::arithmetic::{BaseExt, BnFq2};
+use core::cmp::Ordering;
 use core::convert::TryInto;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
 use rand::RngCore;
-use std::cmp::Ordering;
+#[cfg(feature = "std")]
 use std::io::{self, Read, Write};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -126,13 +130,17 @@ impl Fq2 {
     pub fn from_bytes(bytes: &[u8; 64]) -> CtOption<Fq2> {
         let c0 = Fq::from_bytes(bytes[0..32].try_into().unwrap());
         let c1 = Fq::from_bytes(bytes[32..64].try_into().unwrap());
-        CtOption::new(
-            Fq2 {
-                c0: c0.unwrap(),
-                c1: c1.unwrap(),
-            },
-            c0.is_some() & c1.is_some(),
-        )
+        c0.and_then(|c0| c1.map(|c1| Fq2 { c0, c1 }))
+    }
+
+    /// Converts two 512-bit little endian integers into the coefficients of
+    /// a `Fq2` by reducing each by the modulus, so that uniform input gives a
+    /// statistically uniform element.
+    pub(crate) fn from_uniform_bytes(bytes: &[u8; 128]) -> Fq2 {
+        Fq2 {
+            c0: Fq::from_bytes_wide(bytes[..64].try_into().unwrap()),
+            c1: Fq::from_bytes_wide(bytes[64..].try_into().unwrap()),
+        }
     }
 
     /// Converts an element of `Fq` into a byte representation in
@@ -299,6 +307,17 @@ impl Fq2 {
     }
 }
 
+impl BnFq2<Fq> for Fq2 {
+    fn conjugate(&mut self) {
+        Fq2::conjugate(self)
+    }
+
+    fn mul_by_base(&mut self, c: &Fq) {
+        self.c0 *= c;
+        self.c1 *= c;
+    }
+}
+
 impl Field for Fq2 {
     fn random(mut rng: impl RngCore) -> Self {
         Fq2 {
@@ -394,16 +413,27 @@ impl Field for Fq2 {
     }
 }
 
+/// Domain separation tag for expanding the input of `Fq2::from_bytes_wide`.
+const FROM_BYTES_WIDE_DST: &[u8] = b"pairing_bn256-Fq2-from_bytes_wide";
+
 impl BaseExt for Fq2 {
     const MODULUS: &'static str =
         "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
 
-    /// Converts a 512-bit little endian integer into
-    /// a `Fq` by reducing by the modulus.
-    fn from_bytes_wide(_: &[u8; 64]) -> Self {
-        unimplemented!();
+    /// Maps 64 uniform bytes to a statistically uniform `Fq2`.
+    ///
+    /// 512 bits are too few to reduce into both coefficients without bias, so
+    /// the bytes are first expanded to 64 bytes per coefficient with
+    /// `expand_message_xmd` and then reduced as in `Fq2::from_uniform_bytes`.
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        let mut wide = [0u8; 128];
+        expand_message_xmd(bytes, FROM_BYTES_WIDE_DST, &mut wide);
+        Fq2::from_uniform_bytes(&wide)
     }
+}
 
+#[cfg(feature = "std")]
+impl BaseExtStd for Fq2 {
     /// Writes this element in its normalized, little endian form into a buffer.
     fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         let compressed = self.to_bytes();
@@ -456,6 +486,73 @@ fn test_ser() {
     assert_eq!(a0, a1);
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_read_write() {
+    let a0 = Fq2::rand();
+    let mut buf = vec![];
+    a0.write(&mut buf).unwrap();
+    assert_eq!(buf.len(), 64);
+    let a1 = Fq2::read(&mut &buf[..]).unwrap();
+    assert_eq!(a0, a1);
+
+    // Coefficients that are not reduced are rejected.
+    let a2 = Fq::read(&mut &[0xff; 32][..]);
+    assert!(a2.is_err());
+}
+
+#[test]
+fn test_from_bytes_wide() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        let mut wide = [0u8; 128];
+        expand_message_xmd(&bytes, FROM_BYTES_WIDE_DST, &mut wide);
+        assert_eq!(Fq2::from_bytes_wide(&bytes), Fq2::from_uniform_bytes(&wide));
+    }
+
+    // Changing either half of the input changes both coefficients.
+    let a = Fq2::from_bytes_wide(&[0u8; 64]);
+    let mut bytes = [0u8; 64];
+    bytes[63] = 1;
+    let b = Fq2::from_bytes_wide(&bytes);
+    assert_ne!(a.c0, b.c0);
+    assert_ne!(a.c1, b.c1);
+}
+
+#[test]
+fn test_from_uniform_bytes() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    for _ in 0..100 {
+        let mut bytes = [0u8; 128];
+        rng.fill_bytes(&mut bytes);
+        let a = Fq2::from_uniform_bytes(&bytes);
+        assert_eq!(a.c0, Fq::from_bytes_wide(bytes[..64].try_into().unwrap()));
+        assert_eq!(a.c1, Fq::from_bytes_wide(bytes[64..].try_into().unwrap()));
+    }
+
+    // Every coefficient uses all 64 of its bytes: 2^256 = R mod q.
+    let mut bytes = [0u8; 128];
+    bytes[32] = 1;
+    bytes[96] = 1;
+    let r = Fq::from_bytes_wide(&{
+        let mut r = [0u8; 64];
+        r[32] = 1;
+        r
+    });
+    assert_ne!(r, Fq::zero());
+    assert_eq!(Fq2::from_uniform_bytes(&bytes), Fq2 { c0: r, c1: r });
+}
+
 #[test]
 fn test_fq2_ordering() {
     let mut a = Fq2 {
@@ -1,5 +1,6 @@
 use super::fq::Fq;
 use super::fq2::Fq2;
+use core::convert::TryInto;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::Field;
 use rand::RngCore;
@@ -77,6 +78,26 @@ impl_binops_additive!(Fq6, Fq6);
 impl_binops_multiplicative!(Fq6, Fq6);
 
 impl Fq6 {
+    /// Attempts to convert a little-endian byte representation of the
+    /// coefficients `c0`, `c1`, `c2` into a `Fq6`, failing if any of them is
+    /// not canonical.
+    pub fn from_bytes(bytes: &[u8; 192]) -> CtOption<Fq6> {
+        let c0 = Fq2::from_bytes(bytes[0..64].try_into().unwrap());
+        let c1 = Fq2::from_bytes(bytes[64..128].try_into().unwrap());
+        let c2 = Fq2::from_bytes(bytes[128..192].try_into().unwrap());
+        c0.and_then(|c0| c1.and_then(|c1| c2.map(|c2| Fq6 { c0, c1, c2 })))
+    }
+
+    /// Converts an element of `Fq6` into a byte representation of its
+    /// coefficients `c0`, `c1`, `c2` in little-endian byte order.
+    pub fn to_bytes(self) -> [u8; 192] {
+        let mut res = [0u8; 192];
+        res[0..64].copy_from_slice(&self.c0.to_bytes());
+        res[64..128].copy_from_slice(&self.c1.to_bytes());
+        res[128..192].copy_from_slice(&self.c2.to_bytes());
+        res
+    }
+
     pub fn mul_assign(&mut self, other: &Self) {
         let mut a_a = self.c0;
         let mut b_b = self.c1;
@@ -233,7 +254,7 @@ impl Fq6 {
 
     /// Multiply by cubic nonresidue v.
     pub fn mul_by_nonresidue(&mut self) {
-        use std::mem::swap;
+        use core::mem::swap;
         swap(&mut self.c0, &mut self.c1);
         swap(&mut self.c0, &mut self.c2);
         // c0, c1, c2 -> c2, c0, c1
@@ -242,7 +263,7 @@ impl Fq6 {
 
     /// Multiply by cubic nonresidue v.
     pub fn mul_by_v(&mut self) {
-        use std::mem::swap;
+        use core::mem::swap;
         swap(&mut self.c0, &mut self.c1);
         swap(&mut self.c0, &mut self.c2);
 
@@ -2,12 +2,15 @@
 use super::assembly::assembly_field;
 use super::common::common_field;
 use super::LegendreSymbol;
+#[cfg(feature = "std")]
+use crate::arithmetic::BaseExtStd;
 use crate::arithmetic::{adc, mac, sbb, BaseExt, FieldExt, Group};
 use core::convert::TryInto;
 use core::fmt;
 use core::ops::{Add, Mul, Neg, Sub};
 use ff::PrimeField;
 use rand::RngCore;
+#[cfg(feature = "std")]
 use std::io::{self, Read, Write};
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
@@ -64,6 +67,15 @@ const ROOT_OF_UNITY: Fr = Fr::from_raw([
     0x03ddb9f5166d18b7,
 ]);
 
+/// (T - 1) / 2 where MODULUS - 1 = 2^S * T
+/// 0x183227397098d014dc2822db40c0ac2e9419f4243cdcb848a1f0fac9f
+const T_MINUS1_OVER2: [u64; 4] = [
+    0xcdcb848a1f0fac9f,
+    0x0c0ac2e9419f4243,
+    0x098d014dc2822db4,
+    0x0000000183227397,
+];
+
 const BASEEXT_MODULUS: &'static str =
     "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
 
@@ -155,7 +167,42 @@ impl ff::Field for Fr {
 
     /// Computes the square root of this element, if it exists.
     fn sqrt(&self) -> CtOption<Self> {
-        unimplemented!()
+        // Tonelli-Shank's algorithm for q mod 16 = 1
+        // https://eprint.iacr.org/2012/685.pdf (page 12, algorithm 5)
+
+        // w = self^((t - 1) // 2)
+        let w = self.pow(&T_MINUS1_OVER2);
+
+        let mut v = S;
+        let mut x = self * w;
+        let mut b = x * w;
+
+        // Initialize z as the 2^S root of unity.
+        let mut z = ROOT_OF_UNITY;
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b.square();
+            let mut j_less_than_v: Choice = 1.into();
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Self::one());
+                let squared = Self::conditional_select(&tmp, &z, tmp_is_one).square();
+                tmp = Self::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Self::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Self::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let result = x * z;
+            x = Self::conditional_select(&result, &x, b.ct_eq(&Self::one()));
+            z = z.square();
+            b *= z;
+            v = k;
+        }
+
+        CtOption::new(x, x.square().ct_eq(self))
     }
 
     /// Computes the multiplicative inverse of this element,
@@ -238,6 +285,10 @@ impl ff::PrimeField for Fr {
 
 #[cfg(test)]
 use ff::Field;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand_xorshift::XorShiftRng;
 
 #[test]
 fn test_zeta() {
@@ -258,6 +309,59 @@ fn test_root_of_unity() {
     );
 }
 
+#[test]
+fn test_delta() {
+    assert_eq!(Fr::DELTA, GENERATOR.pow(&[1u64 << Fr::S, 0, 0, 0]));
+    assert_eq!(
+        Fr::DELTA.pow(&[
+            0x9b9709143e1f593f,
+            0x181585d2833e8487,
+            0x131a029b85045b68,
+            0x000000030644e72e,
+        ]),
+        Fr::one()
+    );
+}
+
+#[test]
+fn test_sqrt() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    // The generator and the 2^S root of unity are both nonresidues.
+    assert!(bool::from(GENERATOR.sqrt().is_none()));
+    assert!(bool::from(ROOT_OF_UNITY.sqrt().is_none()));
+    assert_eq!(Fr::zero().sqrt().unwrap(), Fr::zero());
+
+    for _ in 0..10000 {
+        let a = Fr::random(&mut rng);
+        let b = a.square();
+
+        let b = b.sqrt().unwrap();
+        assert!(a == b || a == -b);
+
+        // Exactly one of a and a * g is a square.
+        let c = a * GENERATOR;
+        assert!(bool::from(a.sqrt().is_some() ^ c.sqrt().is_some()));
+    }
+
+    let mut c = Fr::one();
+    for _ in 0..10000 {
+        let mut b = c.square();
+        b = b.sqrt().unwrap();
+
+        if b != c {
+            b = -b;
+        }
+
+        assert_eq!(b, c);
+
+        c += &Fr::one();
+    }
+}
+
 #[test]
 fn test_inv_root_of_unity() {
     assert_eq!(Fr::ROOT_OF_UNITY_INV, Fr::root_of_unity().invert().unwrap());
@@ -1,8 +1,11 @@
-use crate::arithmetic::{BaseExt, Coordinates, CurveAffine, CurveExt, Group};
+use crate::arithmetic::{mac, BaseExt, Coordinates, CurveAffine, CurveExt, FieldExt, Group};
+use crate::bn256::fq6::FROBENIUS_COEFF_FQ6_C1;
 use crate::bn256::Fq;
 use crate::bn256::Fq2;
 use crate::bn256::Fr;
+use crate::bn256::XI_TO_Q_MINUS_1_OVER_2;
 use core::cmp;
+use core::convert::TryInto;
 use core::fmt::Debug;
 use core::iter::Sum;
 use core::ops::{Add, Mul, Neg, Sub};
@@ -18,10 +21,12 @@ new_curve_impl!(
     G1,
     G1Affine,
     G1Compressed,
+    G1Uncompressed,
     Fq,
     Fr,
     (G1_GENERATOR_X,G1_GENERATOR_Y),
     G1_B,
+    G1_ENDO_ZETA,
     "bn256_g1"
 );
 
@@ -30,10 +35,12 @@ new_curve_impl!(
     G2,
     G2Affine,
     G2Compressed,
+    G2Uncompressed,
     Fq2,
     Fr,
     (G2_GENERATOR_X, G2_GENERATOR_Y),
     G2_B,
+    G2_ENDO_ZETA,
     "bn256_g2"
 );
 
@@ -41,6 +48,97 @@ const G1_GENERATOR_X: Fq = Fq::one();
 const G1_GENERATOR_Y: Fq = Fq::from_raw([2, 0, 0, 0]);
 const G1_B: Fq = Fq::from_raw([3, 0, 0, 0]);
 
+// The cube root of unity in Fq that maps (x, y) to (zeta * x, y). On G1
+// this is multiplication by Fr::ZETA.
+const G1_ENDO_ZETA: Fq = Fq::ZETA;
+
+// Constants for the GLV decomposition of a scalar k into k1 + k2 * Fr::ZETA
+// using the short lattice basis ((a1, b1), (a2, b2)) of {(x, y) : x + y * ZETA = 0 mod r}.
+// See https://www.iacr.org/archive/crypto2001/21390189.pdf, section 4.
+
+/// round(b2 * 2^256 / r)
+const GLV_GAMMA1: [u64; 3] = [0x5398fd0300ff6565, 0x4ccef014a773d2d2, 0x2];
+
+/// round(-b1 * 2^256 / r)
+const GLV_GAMMA2: [u64; 3] = [0xd91d232ec7e0b3d7, 0x2, 0x0];
+
+/// -b1 = 0x89d3256894d213e3
+const GLV_B1: Fr = Fr::from_raw([0x89d3256894d213e3, 0, 0, 0]);
+
+/// b2 = 0x6f4d8248eeb859fd0be4e1541221250b
+const GLV_B2: Fr = Fr::from_raw([0x0be4e1541221250b, 0x6f4d8248eeb859fd, 0, 0]);
+
+/// Computes floor(a * b / 2^256).
+fn mul_shr_256(a: &[u64; 4], b: &[u64; 3]) -> [u64; 4] {
+    let mut t = [0u64; 7];
+    for i in 0..4 {
+        let mut carry = 0;
+        for j in 0..3 {
+            let (v, c) = mac(t[i + j], a[i], b[j], carry);
+            t[i + j] = v;
+            carry = c;
+        }
+        t[i + 3] = carry;
+    }
+    [t[4], t[5], t[6], 0]
+}
+
+/// Splits `k` into `k1 + k2 * Fr::ZETA`, returning the absolute values of
+/// `k1` and `k2`, which are below 2^128, together with their signs.
+fn glv_decompose(k: &Fr) -> [(u128, Choice); 2] {
+    let repr = k.to_repr();
+    let mut limbs = [0u64; 4];
+    for (limb, bytes) in limbs.iter_mut().zip(repr.chunks(8)) {
+        *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let c1 = Fr::from_raw(mul_shr_256(&limbs, &GLV_GAMMA1));
+    let c2 = Fr::from_raw(mul_shr_256(&limbs, &GLV_GAMMA2));
+
+    let k2 = c1 * GLV_B1 - c2 * GLV_B2;
+    let k1 = k - k2 * Fr::ZETA;
+
+    let abs = |k: Fr| {
+        // k is "negative" when its canonical form does not fit in 128 bits.
+        let repr = k.to_repr();
+        let is_neg = !repr[16..].iter().fold(0u8, |acc, b| acc | b).ct_eq(&0);
+        let k = Fr::conditional_select(&k, &-k, is_neg);
+        (k.get_lower_128(), is_neg)
+    };
+
+    [abs(k1), abs(k2)]
+}
+
+impl G1 {
+    /// Multiplies this point by `by` using the GLV method: the scalar is split
+    /// into two halves of about 128 bits that are applied to the point and its
+    /// image under the endomorphism with a joint double-and-add.
+    fn mul_scalar(&self, by: &Fr) -> G1 {
+        let [(k1, k1_neg), (k2, k2_neg)] = glv_decompose(by);
+
+        let p1 = G1::conditional_select(self, &-self, k1_neg);
+        let p2 = self.endo();
+        let p2 = G1::conditional_select(&p2, &-p2, k2_neg);
+        let p1_plus_p2 = p1 + p2;
+
+        let mut acc = G1::identity();
+        for i in (0..128).rev() {
+            acc = acc.double();
+
+            let b1 = Choice::from(((k1 >> i) & 1) as u8);
+            let b2 = Choice::from(((k2 >> i) & 1) as u8);
+
+            // Always add one of p1, p2 or p1 + p2 and keep the sum only if at
+            // least one of the bits is set.
+            let addend = G1::conditional_select(&p1, &p2, b2);
+            let addend = G1::conditional_select(&addend, &p1_plus_p2, b1 & b2);
+            acc = G1::conditional_select(&acc, &(acc + addend), b1 | b2);
+        }
+
+        acc
+    }
+}
+
 impl group::cofactor::CofactorGroup for G1 {
     type Subgroup = G1;
 
@@ -72,6 +170,12 @@ const G2_B: Fq2 = Fq2 {
     ]),
 };
 
+// The same cube root of unity acts on G2 as multiplication by Fr::ZETA^2.
+const G2_ENDO_ZETA: Fq2 = Fq2 {
+    c0: Fq::ZETA,
+    c1: Fq::zero(),
+};
+
 const G2_GENERATOR_X: Fq2 = Fq2 {
     c0: Fq::from_raw([
         0x46debd5cd992f6ed,
@@ -128,33 +232,187 @@ impl CofactorGroup for G2 {
     }
 
     fn into_subgroup(self) -> CtOption<Self::Subgroup> {
-        unimplemented!();
+        CtOption::new(self, self.is_torsion_free())
     }
 
     fn is_torsion_free(&self) -> Choice {
-        // "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
-        let e: [u8; 32] = [
-            0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81,
-            0x58, 0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93,
-            0xf0, 0x00, 0x00, 0x01,
-        ];
+        // For BN curves a point P on the twist lies in the order-r subgroup
+        // if and only if [x + 1]P + psi([x]P) + psi^2([x]P) == psi^3([2x]P),
+        // see https://eprint.iacr.org/2022/348.pdf, section 5.1. This only
+        // multiplies by x, where psi(P) == [6x^2]P multiplies by 6x^2.
+        let x_p = self.mul_by_constant(crate::bn256::BN_X as u128);
+        let lhs = x_p + self + x_p.psi() + x_p.psi().psi();
+        let rhs = x_p.double().psi().psi().psi();
+        lhs.ct_eq(&rhs)
+    }
+}
 
-        // self * GROUP_ORDER;
+/// 6x^2 where x is the BN parameter; the eigenvalue of psi on G2.
+pub(crate) const SIX_X_SQUARED: u128 = 0x6f4d8248eeb859fbf83e9682e87cfd46;
 
+impl G2 {
+    /// Applies the untwist-Frobenius-twist endomorphism psi to this point.
+    /// On G2 this acts as multiplication by 6x^2, where x is the BN parameter.
+    pub fn psi(&self) -> Self {
+        // The Frobenius acts on Fq2 as conjugation, so in Jacobian coordinates
+        // psi(X, Y, Z) = (conj(X) * xi^((q - 1) / 3), conj(Y) * xi^((q - 1) / 2), conj(Z)).
+        let mut x = self.x;
+        x.conjugate();
+        x.mul_assign(&FROBENIUS_COEFF_FQ6_C1[1]);
+
+        let mut y = self.y;
+        y.conjugate();
+        y.mul_assign(&XI_TO_Q_MINUS_1_OVER_2);
+
+        let mut z = self.z;
+        z.conjugate();
+
+        G2 { x, y, z }
+    }
+
+    fn mul_by_constant(&self, by: u128) -> Self {
+        // The multiplier is public, so branching on its bits leaks nothing.
         let mut acc = G2::identity();
-        for bit in e
-            .iter()
-            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-            .skip(1)
-        {
+        for i in (0..128 - by.leading_zeros()).rev() {
             acc = acc.double();
-            acc = G2::conditional_select(&acc, &(acc + self), bit);
+            if (by >> i) & 1 == 1 {
+                acc += self;
+            }
         }
-        acc.is_identity()
+        acc
+    }
+
+    /// Maps any point of the twist into G2 by computing
+    /// [x]P + psi([3x]P) + psi^2([x]P) + psi^3(P), which is much cheaper than
+    /// multiplying by the cofactor, see
+    /// https://eprint.iacr.org/2017/419.pdf, section 4.1.
+    pub(crate) fn clear_cofactor_psi(&self) -> Self {
+        let x_p = self.mul_by_constant(crate::bn256::BN_X as u128);
+        let three_x_p = x_p.double() + x_p;
+
+        x_p + three_x_p.psi() + x_p.psi().psi() + self.psi().psi().psi()
+    }
+}
+
+// Constants for the GLS decomposition of a scalar k into
+// k0 + k1 * l + k2 * l^2 + k3 * l^3 where l = 6x^2 is the eigenvalue of psi,
+// using the reduced basis of {(k0, k1, k2, k3) : k0 + k1 * l + k2 * l^2 + k3 * l^3 = 0 mod r}
+//   v0 = (2x + 1,     0,       2x,   1)
+//   v1 = (2x,     x + 1,       -x,   x)
+//   v2 = (x + 1,      x,        x, -2x)
+//   v3 = (2x + 1,    -x, -(x + 1),  -x)
+// See https://eprint.iacr.org/2008/117.pdf, section 4.
+
+/// The BN parameter x.
+const BN_X: Fr = Fr::from_raw([0x44e992b44a6909f1, 0, 0, 0]);
+
+/// round(alpha_i * 2^256) where (1, 0, 0, 0) = sum_i alpha_i * v_i over the rationals.
+const GLS_GAMMA: [[u64; 3]; 4] = [
+    [0x2dff291532e42728, 0x55b4ca7ba3e5577f, 0x9e80318ab0d92b95],
+    [0x46f4bda995d51bb1, 0x08e5da66fc7184ae, 0x9e80318ab0d92b93],
+    [0xd91d232ec7e0b3d7, 0x2, 0x0],
+    [0xc170977dcef3cd3f, 0x55b4ca7ba3e5577d, 0x9e80318ab0d92b95],
+];
+
+/// The components of a GLS decomposition are below 2^GLS_BITS.
+pub(crate) const GLS_BITS: usize = 66;
+
+/// Splits `k` into `k0 + k1 * l + k2 * l^2 + k3 * l^3` where `l` is the
+/// eigenvalue of psi on G2, and of the Frobenius map on Gt, returning the
+/// absolute values of the `k_i`, which are below 2^66, together with their signs.
+pub(crate) fn gls_decompose(k: &Fr) -> [(u128, Choice); 4] {
+    let repr = k.to_repr();
+    let mut limbs = [0u64; 4];
+    for (limb, bytes) in limbs.iter_mut().zip(repr.chunks(8)) {
+        *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    let mut c = [Fr::zero(); 4];
+    for (c, gamma) in c.iter_mut().zip(GLS_GAMMA.iter()) {
+        *c = Fr::from_raw(mul_shr_256(&limbs, gamma));
     }
+
+    let x = BN_X;
+    let two_x = x.double();
+    let x_plus_one = x + Fr::one();
+    let two_x_plus_one = two_x + Fr::one();
+
+    // (k, 0, 0, 0) - sum_i c_i * v_i
+    let k0 = k - (c[0] * two_x_plus_one + c[1] * two_x + c[2] * x_plus_one + c[3] * two_x_plus_one);
+    let k1 = -(c[1] * x_plus_one + c[2] * x - c[3] * x);
+    let k2 = -(c[0] * two_x - c[1] * x + c[2] * x - c[3] * x_plus_one);
+    let k3 = -(c[0] + c[1] * x - c[2] * two_x - c[3] * x);
+
+    let abs = |k: Fr| {
+        // k is "negative" when its canonical form does not fit in 128 bits.
+        let repr = k.to_repr();
+        let is_neg = !repr[16..].iter().fold(0u8, |acc, b| acc | b).ct_eq(&0);
+        let k = Fr::conditional_select(&k, &-k, is_neg);
+        (k.get_lower_128(), is_neg)
+    };
+
+    [abs(k0), abs(k1), abs(k2), abs(k3)]
 }
 
 impl G2 {
+    /// Multiplies this point by `by` with [`G2::mul_gls`] if the point is in
+    /// the prime order subgroup, as every point from the checked constructors
+    /// is, and with a constant-time double-and-add otherwise. The choice only
+    /// depends on the point, and both paths are constant time in `by`.
+    fn mul_scalar(&self, by: &Fr) -> G2 {
+        if bool::from(self.is_torsion_free()) {
+            self.mul_gls(by)
+        } else {
+            self.mul_double_and_add(by)
+        }
+    }
+
+    /// Multiplies this point by `by` using the GLS method: the scalar is split
+    /// into four parts of about 64 bits that are applied to the point and its
+    /// images under psi, psi^2 and psi^3 with a joint double-and-add over a
+    /// table of all 16 subset sums, in constant time.
+    ///
+    /// The result is only correct for points in the prime order subgroup, on
+    /// which psi acts as multiplication by 6x^2. `G2 * Fr` checks this first,
+    /// so only call this directly on points known to be in the subgroup.
+    pub fn mul_gls(&self, by: &Fr) -> G2 {
+        let ks = gls_decompose(by);
+
+        let mut bases = [*self; 4];
+        for i in 1..4 {
+            bases[i] = bases[i - 1].psi();
+        }
+        for (base, (_, is_neg)) in bases.iter_mut().zip(ks.iter()) {
+            *base = G2::conditional_select(base, &-*base, *is_neg);
+        }
+
+        // table[i] is the sum of the bases selected by the bits of i.
+        let mut table = [G2::identity(); 16];
+        for i in 1..16usize {
+            let low = i.trailing_zeros() as usize;
+            table[i] = table[i & (i - 1)] + bases[low];
+        }
+
+        let mut acc = G2::identity();
+        for i in (0..GLS_BITS).rev() {
+            acc = acc.double();
+
+            let index = ks.iter().enumerate().fold(0u8, |index, (j, (k, _))| {
+                index | ((((k >> i) & 1) as u8) << j)
+            });
+
+            // Scan the whole table so the memory access pattern does not
+            // depend on the scalar, and keep the sum only if index != 0.
+            let mut addend = G2::identity();
+            for (j, entry) in table.iter().enumerate() {
+                addend = G2::conditional_select(&addend, entry, index.ct_eq(&(j as u8)));
+            }
+            acc = G2::conditional_select(&acc, &(acc + addend), !index.ct_eq(&0));
+        }
+
+        acc
+    }
+
     pub fn random(mut rng: impl RngCore) -> Self {
         let mut point = <Self as group::Group>::random(&mut rng);
         point = point.clear_cofactor();
@@ -165,13 +423,16 @@ impl G2 {
 #[cfg(test)]
 mod tests {
 
-    use crate::bn256::{G1, G2};
+    use super::{gls_decompose, glv_decompose, GLS_BITS, SIX_X_SQUARED};
+    use crate::arithmetic::FieldExt;
+    use crate::bn256::{Fr, G1Affine, G2Affine, G1, G2};
     use ff::Field;
 
     use crate::arithmetic::{CurveAffine, CurveExt};
-    use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine};
+    use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, Group, UncompressedEncoding};
     use rand::SeedableRng;
     use rand_xorshift::XorShiftRng;
+    use subtle::{Choice, ConditionallySelectable};
 
     fn is_on_curve<G: CurveExt>() {
         assert!(bool::from(G::identity().is_on_curve()));
@@ -414,6 +675,291 @@ mod tests {
         assert_eq!(t0, t1);
     }
 
+    fn uncompressed_encoding<G: CurveExt>()
+    where
+        G::AffineExt: UncompressedEncoding,
+    {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let identity = G::AffineExt::identity();
+        let bytes = identity.to_uncompressed();
+        assert!(bool::from(
+            G::AffineExt::from_uncompressed(&bytes)
+                .unwrap()
+                .is_identity()
+        ));
+
+        for _ in 0..100 {
+            let point: G::AffineExt = (G::generator() * G::ScalarExt::random(&mut rng)).into();
+            let bytes = point.to_uncompressed();
+            assert_eq!(point, G::AffineExt::from_uncompressed(&bytes).unwrap());
+            assert_eq!(
+                point,
+                G::AffineExt::from_uncompressed_unchecked(&bytes).unwrap()
+            );
+
+            // Setting the infinity flag on a point that is not all zero is rejected.
+            let mut bytes = point.to_uncompressed();
+            let len = bytes.as_ref().len();
+            bytes.as_mut()[len - 1] |= 1 << 6;
+            assert!(bool::from(
+                G::AffineExt::from_uncompressed_unchecked(&bytes).is_none()
+            ));
+
+            // So is the reserved flag.
+            let mut bytes = point.to_uncompressed();
+            bytes.as_mut()[len - 1] |= 1 << 7;
+            assert!(bool::from(
+                G::AffineExt::from_uncompressed_unchecked(&bytes).is_none()
+            ));
+
+            // Points off the curve are rejected by the checked decoding only.
+            let mut bytes = point.to_uncompressed();
+            bytes.as_mut()[0] ^= 1;
+            assert!(bool::from(
+                G::AffineExt::from_uncompressed(&bytes).is_none()
+            ));
+            assert!(bool::from(
+                G::AffineExt::from_uncompressed_unchecked(&bytes).is_some()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_g2_uncompressed_subgroup_check() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let point = <G2 as group::Group>::random(&mut rng);
+        assert!(!bool::from(point.is_torsion_free()));
+        let bytes = G2Affine::from(point).to_uncompressed();
+        assert!(bool::from(G2Affine::from_uncompressed(&bytes).is_none()));
+        assert!(bool::from(
+            G2Affine::from_uncompressed_unchecked(&bytes).is_some()
+        ));
+    }
+
+    fn is_torsion_free_by_order(p: &G2) -> bool {
+        // "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+        let e: [u8; 32] = [
+            0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81,
+            0x58, 0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93,
+            0xf0, 0x00, 0x00, 0x01,
+        ];
+
+        // p * GROUP_ORDER
+        let mut acc = G2::identity();
+        for bit in e
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
+            .skip(1)
+        {
+            acc = acc.double();
+            acc = G2::conditional_select(&acc, &(acc + p), bit);
+        }
+        acc.is_identity().into()
+    }
+
+    #[test]
+    fn test_psi() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert!(bool::from(G2::identity().psi().is_identity()));
+
+        for _ in 0..100 {
+            // psi is a group endomorphism of the whole twist.
+            let a = <G2 as group::Group>::random(&mut rng);
+            let b = <G2 as group::Group>::random(&mut rng);
+            assert!(bool::from(a.psi().is_on_curve()));
+            assert_eq!((a + b).psi(), a.psi() + b.psi());
+
+            // On G2 it acts as multiplication by 6x^2.
+            let c = G2::random(&mut rng);
+            assert_eq!(c.psi(), c.mul_by_constant(SIX_X_SQUARED));
+        }
+    }
+
+    #[test]
+    fn test_is_torsion_free() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        assert!(bool::from(G2::identity().is_torsion_free()));
+        assert!(bool::from(G2::generator().is_torsion_free()));
+
+        for _ in 0..100 {
+            let a = <G2 as group::Group>::random(&mut rng);
+            let b = a.clear_cofactor();
+            let c = b + a;
+
+            for p in [a, b, c] {
+                let fast = bool::from(p.is_torsion_free());
+                assert_eq!(fast, is_torsion_free_by_order(&p));
+                assert_eq!(fast, bool::from(p.into_subgroup().is_some()));
+            }
+            assert!(!bool::from(a.is_torsion_free()));
+            assert!(bool::from(b.is_torsion_free()));
+            assert!(!bool::from(c.is_torsion_free()));
+        }
+    }
+
+    #[test]
+    fn test_endo() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..100 {
+            let a = G1::random(&mut rng);
+            assert!(bool::from(a.endo().is_on_curve()));
+            assert_eq!(a.endo(), a.mul_double_and_add(&Fr::ZETA));
+
+            let b = G2::random(&mut rng);
+            assert!(bool::from(b.endo().is_on_curve()));
+            assert_eq!(b.endo(), b.mul_double_and_add(&Fr::ZETA.square()));
+        }
+    }
+
+    #[test]
+    fn test_glv_decompose() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let edge_cases = [
+            Fr::zero(),
+            Fr::one(),
+            -Fr::one(),
+            Fr::ZETA,
+            -Fr::ZETA,
+            Fr::TWO_INV,
+        ];
+        for k in edge_cases
+            .iter()
+            .cloned()
+            .chain((0..10000).map(|_| Fr::random(&mut rng)))
+        {
+            let [(k1, k1_neg), (k2, k2_neg)] = glv_decompose(&k);
+            let k1 = Fr::from_u128(k1);
+            let k1 = Fr::conditional_select(&k1, &-k1, k1_neg);
+            let k2 = Fr::from_u128(k2);
+            let k2 = Fr::conditional_select(&k2, &-k2, k2_neg);
+            assert_eq!(k, k1 + k2 * Fr::ZETA);
+        }
+    }
+
+    #[test]
+    fn test_glv_mul() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let p = G1::random(&mut rng);
+        for k in [Fr::zero(), Fr::one(), -Fr::one(), Fr::ZETA, -Fr::ZETA] {
+            assert_eq!(p * k, p.mul_double_and_add(&k));
+        }
+        assert!(bool::from(
+            (G1::identity() * Fr::random(&mut rng)).is_identity()
+        ));
+
+        for _ in 0..1000 {
+            let p = G1::random(&mut rng);
+            let k = Fr::random(&mut rng);
+            assert_eq!(p * k, p.mul_double_and_add(&k));
+            assert_eq!(G1Affine::from(p) * k, p.mul_double_and_add(&k));
+        }
+    }
+
+    #[test]
+    fn test_gls_decompose() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let l = Fr::from_u128(SIX_X_SQUARED);
+        let edge_cases = [Fr::zero(), Fr::one(), -Fr::one(), l, -l, Fr::TWO_INV];
+        for k in edge_cases
+            .iter()
+            .cloned()
+            .chain((0..10000).map(|_| Fr::random(&mut rng)))
+        {
+            let mut acc = Fr::zero();
+            for (k_i, is_neg) in gls_decompose(&k).iter().rev() {
+                assert!(*k_i < 1 << GLS_BITS);
+                let k_i = Fr::from_u128(*k_i);
+                acc = acc * l + Fr::conditional_select(&k_i, &-k_i, *is_neg);
+            }
+            assert_eq!(k, acc);
+        }
+    }
+
+    #[test]
+    fn test_gls_mul() {
+        use ark_std::{end_timer, start_timer};
+
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        let p = G2::random(&mut rng);
+        let l = Fr::from_u128(SIX_X_SQUARED);
+        for k in [Fr::zero(), Fr::one(), -Fr::one(), l, -l] {
+            assert_eq!(p.mul_gls(&k), p.mul_double_and_add(&k));
+        }
+        assert!(bool::from(
+            G2::identity().mul_gls(&Fr::random(&mut rng)).is_identity()
+        ));
+
+        for _ in 0..100 {
+            let p = G2::random(&mut rng);
+            let k = Fr::random(&mut rng);
+            assert_eq!(p.mul_gls(&k), p.mul_double_and_add(&k));
+            assert_eq!(p * k, p.mul_double_and_add(&k));
+        }
+
+        // Outside the subgroup psi is not multiplication by 6x^2, so G2 * Fr
+        // falls back to double-and-add to give the right multiple.
+        for _ in 0..10 {
+            let p = <G2 as Group>::random(&mut rng);
+            assert!(!bool::from(p.is_torsion_free()));
+            let k = Fr::random(&mut rng);
+            assert_eq!(p * k, p.mul_double_and_add(&k));
+            assert_eq!(G2Affine::from(p) * k, p.mul_double_and_add(&k));
+            assert_ne!(p.mul_gls(&k), p.mul_double_and_add(&k));
+        }
+
+        let n = 100;
+        let p = G2::random(&mut rng);
+        let k = Fr::random(&mut rng);
+        for (name, mul) in [
+            ("G2 * Fr", (|p: &G2, k: &Fr| p * k) as fn(&G2, &Fr) -> G2),
+            ("G2::mul_double_and_add", |p, k| p.mul_double_and_add(k)),
+            ("G2::mul_gls", |p, k| p.mul_gls(k)),
+        ] {
+            let message = format!("{}, {} times", name, n);
+            let start = start_timer!(|| message);
+            for _ in 0..n {
+                assert!(!bool::from(mul(&p, &k).is_identity()));
+            }
+            end_timer!(start);
+        }
+    }
+
     #[test]
     fn test_cofactor() {
         let mut rng = XorShiftRng::from_seed([
@@ -428,6 +974,31 @@ mod tests {
         assert!(bool::from(a.is_torsion_free()));
     }
 
+    #[test]
+    fn test_clear_cofactor_psi() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            let a = <G2 as group::Group>::random(&mut rng);
+            assert!(!bool::from(a.is_torsion_free()));
+            let b = a.clear_cofactor_psi();
+            assert!(bool::from(b.is_torsion_free()));
+            assert!(is_torsion_free_by_order(&b));
+        }
+
+        // On G2 the map acts as multiplication by x + 3x * l + x * l^2 + l^3,
+        // where l = 6x^2 is the eigenvalue of psi.
+        let x = Fr::from(crate::bn256::BN_X);
+        let l = Fr::from_u128(SIX_X_SQUARED);
+        let k = x + (x.double() + x) * l + x * l.square() + l.square() * l;
+        assert!(!bool::from(k.is_zero()));
+        let a = G2::random(&mut rng);
+        assert_eq!(a.clear_cofactor_psi(), a * k);
+    }
+
     #[test]
     fn curve_tests() {
         is_on_curve::<G1>();
@@ -444,37 +1015,7 @@ mod tests {
         mixed_addition::<G2>();
         multiplication::<G2>();
         batch_normalize::<G1>();
-    }
-}
-
-impl group::UncompressedEncoding for G1Affine {
-    type Uncompressed = G1Compressed;
-
-    fn from_uncompressed(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn from_uncompressed_unchecked(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn to_uncompressed(&self) -> Self::Uncompressed {
-        unimplemented!();
-    }
-}
-
-impl group::UncompressedEncoding for G2Affine {
-    type Uncompressed = G2Compressed;
-
-    fn from_uncompressed(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn from_uncompressed_unchecked(_: &Self::Uncompressed) -> CtOption<Self> {
-        unimplemented!();
-    }
-
-    fn to_uncompressed(&self) -> Self::Uncompressed {
-        unimplemented!();
+        uncompressed_encoding::<G1>();
+        uncompressed_encoding::<G2>();
     }
 }
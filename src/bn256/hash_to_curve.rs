@@ -0,0 +1,526 @@
+//! This module implements hashing to the BN254 curve as specified in
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html), using
+//! `expand_message_xmd` with SHA-256 and the Shallue–van de Woestijne map.
+
+use crate::arithmetic::BaseExt;
+use crate::bn256::{Fq, Fq2, G1, G2};
+use ff::Field;
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// Output size of SHA-256 in bytes.
+const B_IN_BYTES: usize = 32;
+
+/// Input block size of SHA-256 in bytes.
+const S_IN_BYTES: usize = 64;
+
+/// Fills `out` with `expand_message_xmd(msg, dst, out.len())` using SHA-256,
+/// see RFC 9380, section 5.3.1.
+///
+/// Panics if `out` is longer than 255 * 32 bytes.
+pub(crate) fn expand_message_xmd(msg: &[u8], dst: &[u8], out: &mut [u8]) {
+    let ell = out.len().div_ceil(B_IN_BYTES);
+    assert!(ell <= 255, "requested output is too long");
+
+    // Tags longer than 255 bytes are replaced by their hash, see section 5.3.3.
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &oversize_dst[..]
+    } else {
+        dst
+    };
+    let dst_len = [dst.len() as u8];
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((out.len() as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    for (i, chunk) in out.chunks_mut(B_IN_BYTES).enumerate() {
+        if i > 0 {
+            let mut tmp = b_0;
+            for (t, b) in tmp.iter_mut().zip(b_i.iter()) {
+                *t ^= b;
+            }
+            b_i = Sha256::new()
+                .chain_update(tmp)
+                .chain_update([i as u8 + 1])
+                .chain_update(dst)
+                .chain_update(dst_len)
+                .finalize();
+        }
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+    }
+}
+
+/// A field that can be hashed to and mapped into a curve over it.
+pub(crate) trait HashToField: Field + ConditionallySelectable {
+    /// Number of bytes of the expanded message used per element, L * m.
+    const OKM_LEN: usize;
+
+    /// Reduces `okm`, which is made of `m` big endian integers of `L` bytes
+    /// each, into a field element.
+    fn from_okm(okm: &[u8]) -> Self;
+
+    /// The `sgn0` function of RFC 9380, section 4.1.
+    fn sgn0(&self) -> Choice;
+}
+
+impl HashToField for Fq {
+    // L = ceil((ceil(log2(q)) + k) / 8) = ceil((254 + 128) / 8)
+    const OKM_LEN: usize = 48;
+
+    fn from_okm(okm: &[u8]) -> Self {
+        let mut bytes = [0u8; 64];
+        for (b, o) in bytes.iter_mut().zip(okm.iter().rev()) {
+            *b = *o;
+        }
+        Fq::from_bytes_wide(&bytes)
+    }
+
+    fn sgn0(&self) -> Choice {
+        Choice::from(self.to_bytes()[0] & 1)
+    }
+}
+
+impl HashToField for Fq2 {
+    const OKM_LEN: usize = 2 * Fq::OKM_LEN;
+
+    fn from_okm(okm: &[u8]) -> Self {
+        // Each coefficient is an L-byte big endian integer, reduced by the
+        // modulus as a 512-bit little endian one.
+        let mut bytes = [0u8; 128];
+        for (wide, e) in bytes.chunks_mut(64).zip(okm.chunks(Fq::OKM_LEN)) {
+            for (b, o) in wide.iter_mut().zip(e.iter().rev()) {
+                *b = *o;
+            }
+        }
+        Fq2::from_uniform_bytes(&bytes)
+    }
+
+    fn sgn0(&self) -> Choice {
+        self.c0.sgn0() | (self.c0.is_zero() & self.c1.sgn0())
+    }
+}
+
+/// Hashes `msg` to two field elements, see RFC 9380, section 5.2.
+pub(crate) fn hash_to_field<F: HashToField>(msg: &[u8], dst: &[u8]) -> [F; 2] {
+    let mut okm = [0u8; 256];
+    let okm = &mut okm[..2 * F::OKM_LEN];
+    expand_message_xmd(msg, dst, okm);
+
+    let (u0, u1) = okm.split_at(F::OKM_LEN);
+    [F::from_okm(u0), F::from_okm(u1)]
+}
+
+/// Constants of the Shallue–van de Woestijne map to `y^2 = x^3 + b`,
+/// see RFC 9380, section 6.6.1.
+pub(crate) struct Svdw<F> {
+    pub(crate) b: F,
+    pub(crate) z: F,
+    /// g(Z)
+    pub(crate) c1: F,
+    /// -Z / 2
+    pub(crate) c2: F,
+    /// sqrt(-g(Z) * 3 * Z^2), with sgn0(c3) == 0
+    pub(crate) c3: F,
+    /// -4 * g(Z) / (3 * Z^2)
+    pub(crate) c4: F,
+}
+
+impl<F: HashToField> Svdw<F> {
+    /// Maps `u` to the affine coordinates of a point on the curve in constant
+    /// time, following the straight-line procedure of RFC 9380, appendix F.1.
+    pub(crate) fn map_to_curve(&self, u: &F) -> (F, F) {
+        let g = |x: &F| x.square() * x + self.b;
+
+        let tv1 = u.square() * self.c1;
+        let tv2 = F::one() + tv1;
+        let tv1 = F::one() - tv1;
+        let tv3 = (tv1 * tv2).invert().unwrap_or(F::zero());
+        let tv4 = *u * tv1 * tv3 * self.c3;
+
+        let x1 = self.c2 - tv4;
+        let e1 = g(&x1).sqrt().is_some();
+
+        let x2 = self.c2 + tv4;
+        let e2 = g(&x2).sqrt().is_some() & !e1;
+
+        let x3 = (tv2.square() * tv3).square() * self.c4 + self.z;
+
+        let x = F::conditional_select(&x3, &x1, e1);
+        let x = F::conditional_select(&x, &x2, e2);
+
+        // One of g(x1), g(x2) and g(x3) is always a square.
+        let y = g(&x).sqrt().unwrap();
+        let e3 = u.sgn0().ct_eq(&y.sgn0());
+        let y = F::conditional_select(&-y, &y, e3);
+
+        (x, y)
+    }
+}
+
+/// The Shallue–van de Woestijne map for G1 with Z = 1.
+const G1_SVDW: Svdw<Fq> = Svdw {
+    b: Fq::from_raw([3, 0, 0, 0]),
+    z: Fq::one(),
+    c1: Fq::from_raw([4, 0, 0, 0]),
+    // 0x183227397098d014dc2822db40c0ac2ecbc0b548b438e5469e10460b6c3e7ea3
+    c2: Fq::from_raw([
+        0x9e10460b6c3e7ea3,
+        0xcbc0b548b438e546,
+        0xdc2822db40c0ac2e,
+        0x183227397098d014,
+    ]),
+    // 0x16789af3a83522eb353c98fc6b36d713d5d8d1cc5dffffffa
+    c3: Fq::from_raw([
+        0x5d8d1cc5dffffffa,
+        0x53c98fc6b36d713d,
+        0x6789af3a83522eb3,
+        0x0000000000000001,
+    ]),
+    // 0x10216f7ba065e00de81ac1e7808072c9dd2b2385cd7b438469602eb24829a9bd
+    c4: Fq::from_raw([
+        0x69602eb24829a9bd,
+        0xdd2b2385cd7b4384,
+        0xe81ac1e7808072c9,
+        0x10216f7ba065e00d,
+    ]),
+};
+
+/// The Shallue–van de Woestijne map for the twist over Fq2 with Z = 1, the
+/// value `find_z_svdw` of RFC 9380, appendix H.1, returns.
+const G2_SVDW: Svdw<Fq2> = Svdw {
+    // 3 / (u + 9)
+    b: Fq2 {
+        c0: Fq::from_raw([
+            0x3267e6dc24a138e5,
+            0xb5b4c5e559dbefa3,
+            0x81be18991be06ac3,
+            0x2b149d40ceb8aaae,
+        ]),
+        c1: Fq::from_raw([
+            0xe4a2bd0685c315d2,
+            0xa74fa084e52d1852,
+            0xcd2cafadeed8fdf4,
+            0x009713b03af0fed4,
+        ]),
+    },
+    z: Fq2 {
+        c0: Fq::one(),
+        c1: Fq::zero(),
+    },
+    // 1 + b
+    c1: Fq2 {
+        c0: Fq::from_raw([
+            0x3267e6dc24a138e6,
+            0xb5b4c5e559dbefa3,
+            0x81be18991be06ac3,
+            0x2b149d40ceb8aaae,
+        ]),
+        c1: Fq::from_raw([
+            0xe4a2bd0685c315d2,
+            0xa74fa084e52d1852,
+            0xcd2cafadeed8fdf4,
+            0x009713b03af0fed4,
+        ]),
+    },
+    c2: Fq2 {
+        c0: Fq::from_raw([
+            0x9e10460b6c3e7ea3,
+            0xcbc0b548b438e546,
+            0xdc2822db40c0ac2e,
+            0x183227397098d014,
+        ]),
+        c1: Fq::zero(),
+    },
+    c3: Fq2 {
+        c0: Fq::from_raw([
+            0xfcbe57377b5ca1ec,
+            0x2e6da55f90a3e510,
+            0xb801fa95b21af64e,
+            0x29fd332ab7260112,
+        ]),
+        c1: Fq::from_raw([
+            0xb1e9154d01565034,
+            0x5e76f77b1267a846,
+            0xf8408aee24ba0b86,
+            0x303d1eff1426764b,
+        ]),
+    },
+    c4: Fq2 {
+        c0: Fq::from_raw([
+            0x21010b008d4eaf99,
+            0xb4e6a9c08b986767,
+            0x8632fe0eb2ac5a41,
+            0x17365bbe63b1d207,
+        ]),
+        c1: Fq::from_raw([
+            0x388732a995d03755,
+            0xfe164d7f4694786b,
+            0xd689d7aa4209cad8,
+            0x0f57ffe5fc79e19c,
+        ]),
+    },
+};
+
+impl G1 {
+    /// Hashes `msg` to a point of G1 under the domain separation tag `dst`,
+    /// following the BN254G1_XMD:SHA-256_SVDW_RO_ suite of RFC 9380.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> G1 {
+        let [u0, u1] = hash_to_field::<Fq>(msg, dst);
+        // G1 has cofactor 1, so there is no cofactor to clear.
+        G1::map_to_curve(&u0) + G1::map_to_curve(&u1)
+    }
+
+    fn map_to_curve(u: &Fq) -> G1 {
+        let (x, y) = G1_SVDW.map_to_curve(u);
+        G1 { x, y, z: Fq::one() }
+    }
+}
+
+impl G2 {
+    /// Hashes `msg` to a point of G2 under the domain separation tag `dst`,
+    /// following the BN254G2_XMD:SHA-256_SVDW_RO_ suite: the two field
+    /// elements are mapped to the twist with SvdW and the cofactor is cleared
+    /// with psi.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> G2 {
+        let [u0, u1] = hash_to_field::<Fq2>(msg, dst);
+        (G2::map_to_curve(&u0) + G2::map_to_curve(&u1)).clear_cofactor_psi()
+    }
+
+    fn map_to_curve(u: &Fq2) -> G2 {
+        let (x, y) = G2_SVDW.map_to_curve(u);
+        G2 {
+            x,
+            y,
+            z: Fq2::one(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::CurveExt;
+    use crate::bn256::{G1Affine, G2Affine};
+    use group::cofactor::CofactorGroup;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn hex_to_fq(hex: &str) -> Fq {
+        let mut bytes = [0u8; 32];
+        let hex = format!("{:0>64}", hex.trim_start_matches("0x"));
+        for (i, b) in bytes.iter_mut().rev().enumerate() {
+            *b = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        Fq::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380, appendix K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        for (msg, expected) in [
+            (
+                &b""[..],
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                &b"abc"[..],
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+        ] {
+            let mut out = [0u8; 32];
+            expand_message_xmd(msg, dst, &mut out);
+            let out: String = out.iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let a = Fq::random(&mut rng);
+        for u in [Fq::zero(), Fq::one(), -Fq::one(), a] {
+            let p = G1::map_to_curve(&u);
+            assert!(bool::from(p.is_on_curve()));
+        }
+
+        let a = Fq2::random(&mut rng);
+        for u in [Fq2::zero(), Fq2::one(), -Fq2::one(), a] {
+            let p = G2::map_to_curve(&u);
+            assert!(bool::from(p.is_on_curve()));
+        }
+    }
+
+    fn check_svdw<F: HashToField>(svdw: &Svdw<F>) {
+        let g = |x: &F| x.square() * x + svdw.b;
+        let two = F::one().double();
+        let three_z2 = svdw.z.square() * (two + F::one());
+        assert_eq!(svdw.c1, g(&svdw.z));
+        assert_eq!(svdw.c2, -svdw.z * two.invert().unwrap());
+        assert_eq!(svdw.c3.square(), -g(&svdw.z) * three_z2);
+        assert!(!bool::from(svdw.c3.sgn0()));
+        assert_eq!(
+            svdw.c4,
+            -g(&svdw.z) * two.double() * three_z2.invert().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_svdw_constants() {
+        check_svdw(&G1_SVDW);
+        check_svdw(&G2_SVDW);
+    }
+
+    #[test]
+    fn test_sgn0() {
+        assert!(!bool::from(Fq::zero().sgn0()));
+        assert!(bool::from(Fq::one().sgn0()));
+        assert!(!bool::from((-Fq::one()).sgn0()));
+
+        let u = |c0: Fq, c1: Fq| Fq2 { c0, c1 };
+        assert!(!bool::from(u(Fq::zero(), Fq::zero()).sgn0()));
+        assert!(bool::from(u(Fq::zero(), Fq::one()).sgn0()));
+        assert!(!bool::from(u(Fq::zero(), -Fq::one()).sgn0()));
+        assert!(bool::from(u(Fq::one(), -Fq::one()).sgn0()));
+        assert!(!bool::from(u(-Fq::one(), Fq::one()).sgn0()));
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        // Test vectors for the BN254G1_XMD:SHA-256_SVDW_RO_ suite.
+        let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+        let q128 = format!("q128_{}", "q".repeat(128));
+        let a512 = format!("a512_{}", "a".repeat(512));
+        let vectors = [
+            (
+                "",
+                "0x0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+                "0x02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5",
+            ),
+            (
+                "abc",
+                "0x23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+                "0x04142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d",
+            ),
+            (
+                "abcdef0123456789",
+                "0x187dbf1c3c89aceceef254d6548d7163fdfa43084145f92c4c91c85c21442d4a",
+                "0x0abd99d5b0000910b56058f9cc3b0ab0a22d47cf27615f588924fac1e5c63b4d",
+            ),
+            (
+                &q128,
+                "0x00fe2b0743575324fc452d590d217390ad48e5a16cf051bee5c40a2eba233f5c",
+                "0x0794211e0cc72d3cbbdf8e4e5cd6e7d7e78d101ff94862caae8acbe63e9fdc78",
+            ),
+            (
+                &a512,
+                "0x01b05dc540bd79fd0fea4fbb07de08e94fc2e7bd171fe025c479dc212a2173ce",
+                "0x1bf028afc00c0f843d113758968f580640541728cfc6d32ced9779aa613cd9b0",
+            ),
+        ];
+
+        for (msg, x, y) in vectors.iter() {
+            let p = G1Affine::from(G1::hash_to_curve(msg.as_bytes(), dst));
+            assert_eq!(p.x, hex_to_fq(x));
+            assert_eq!(p.y, hex_to_fq(y));
+        }
+    }
+
+    fn hex_to_fq2(c0: &str, c1: &str) -> Fq2 {
+        Fq2 {
+            c0: hex_to_fq(c0),
+            c1: hex_to_fq(c1),
+        }
+    }
+
+    #[test]
+    fn test_hash_to_curve_g2() {
+        // RFC 9380 defines no suite for BN254 G2. BN254G2_XMD:SHA-256_SVDW_RO_
+        // is named after the G1 suite and uses the same steps over Fq2, with
+        // L = 48 per coefficient, Z = 1 and the psi-based cofactor clearing of
+        // https://eprint.iacr.org/2017/419.pdf, section 4.1. These values were
+        // checked against a separate implementation written from the text of
+        // RFC 9380, which derives Z with `find_z_svdw` and the SvdW constants
+        // and psi from their definitions.
+        let dst = b"QUUX-V01-CS02-with-BN254G2_XMD:SHA-256_SVDW_RO_";
+        let q128 = format!("q128_{}", "q".repeat(128));
+        let a512 = format!("a512_{}", "a".repeat(512));
+        let vectors = [
+            (
+                "",
+                [
+                    "0x1192005a0f121921a6d5629946199e4b27ff8ee4d6dd4f9581dc550ade851300",
+                    "0x1747d950a6f23c16156e2171bce95d1189b04148ad12628869ed21c96a8c9335",
+                    "0x0498f6bb5ac309a07d9a8b88e6ff4b8de0d5f27a075830e1eb0e68ea318201d8",
+                    "0x2c9755350ca363ef2cf541005437221c5740086c2e909b71d075152484e845f4",
+                ],
+            ),
+            (
+                "abc",
+                [
+                    "0x16c88b54eec9af86a41569608cd0f60aab43464e52ce7e6e298bf584b94fccd2",
+                    "0x0b5db3ca7e8ef5edf3a33dfc3242357fbccead98099c3eb564b3d9d13cba4efd",
+                    "0x1c42ba524cb74db8e2c680449746c028f7bea923f245e69f89256af2d6c5f3ac",
+                    "0x22d02d2da7f288545ff8789e789902245ab08c6b1d253561eec789ec2c1bd630",
+                ],
+            ),
+            (
+                "abcdef0123456789",
+                [
+                    "0x1435fd84aa43c699230e371f6fea3545ce7e053cbbb06a320296a2b81efddc70",
+                    "0x2a8a360585b6b05996ef69c3c09b2c6fb17afe2b1e944f07559c53178eabf171",
+                    "0x2820188dcdc13ffdca31694942418afa1d6dfaaf259d012fab4da52b0f592e38",
+                    "0x142f08e2441ec431defc24621b73cfe0252d19b243cb55b84bdeb85de039207a",
+                ],
+            ),
+            (
+                &q128,
+                [
+                    "0x2cffc213fb63d00d923cb22cda5a2904837bb93a2fe6e875c532c51744388341",
+                    "0x2718ef38d1bc4347f0266c774c8ef4ee5fa7056cc27a4bd7ecf7a888efb95b26",
+                    "0x232553f728341afa64ce66d00535764557a052e38657594e10074ad28728c584",
+                    "0x2206ec0a9288f31ed78531c37295df3b56c42a1284443ee9893adb1521779001",
+                ],
+            ),
+            (
+                &a512,
+                [
+                    "0x242a0a159f36f87065e7c5170426012087023165ce47a486e53d6e2845ca625a",
+                    "0x17f9f6292998cf18ccc155903c1fe6b6465d40c794a3e1ed644a4182ad639f4a",
+                    "0x2dc5b7b65c9c79e6ef4afab8fbe3083c66d4ce31c78f6621ece17ecc892cf4b3",
+                    "0x18ef4886c818f01fdf309bc9a46dd904273917f85e74ecd0de62460a68122037",
+                ],
+            ),
+        ];
+
+        for (msg, [x0, x1, y0, y1]) in vectors.iter() {
+            let p = G2::hash_to_curve(msg.as_bytes(), dst);
+            assert!(bool::from(p.is_torsion_free()));
+
+            let p = G2Affine::from(p);
+            assert_eq!(p.x, hex_to_fq2(x0, x1));
+            assert_eq!(p.y, hex_to_fq2(y0, y1));
+        }
+    }
+}
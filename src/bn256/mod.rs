@@ -1,11 +1,15 @@
 mod common;
 mod engine;
+pub mod ethereum;
 mod fq;
 mod fq12;
 mod fq2;
 mod fq6;
 mod fr;
 mod g;
+mod hash_to_curve;
+#[cfg(feature = "serde")]
+mod serde;
 
 #[cfg(all(feature = "asm", target_arch = "x86_64"))]
 mod assembly;
@@ -0,0 +1,346 @@
+//! Serde support for field elements, points, `Gt` and `G2Prepared`.
+//!
+//! Fixed size types are serialized as their canonical byte encoding: a tuple
+//! of bytes for binary formats and a hex string for human readable ones.
+//! Every value is validated on deserialization, so points are on the curve and
+//! in the prime order subgroup, field elements are reduced and the line
+//! coefficients of a `G2Prepared` are those of its point.
+
+use crate::bn256::{Fq, Fq2, Fr, G1Affine, G1Compressed, G2Affine, G2Compressed, Gt};
+use core::convert::TryInto;
+use core::fmt;
+use ff::PrimeField;
+use group::{cofactor::CofactorGroup, prime::PrimeCurveAffine, GroupEncoding};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use subtle::CtOption;
+
+/// Displays a byte slice as lowercase hex without allocating.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0u8; 128];
+        for chunk in self.0.chunks(buf.len() / 2) {
+            let out = &mut buf[..2 * chunk.len()];
+            hex::encode_to_slice(chunk, out).map_err(|_| fmt::Error)?;
+            f.write_str(core::str::from_utf8(out).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Hex(bytes))
+    } else {
+        let mut tuple = serializer.serialize_tuple(bytes.len())?;
+        for byte in bytes {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+struct BytesVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes or a hex string of {} characters", N, 2 * N)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let mut bytes = [0u8; N];
+        hex::decode_to_slice(v, &mut bytes)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))?;
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        Ok(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor::<N>)
+    } else {
+        deserializer.deserialize_tuple(N, BytesVisitor::<N>)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` for `$t` through its canonical
+/// `$len` byte encoding, rejecting encodings for which `$from` fails.
+macro_rules! impl_serde {
+    ($t:ident, $len:expr, $to:expr, $from:expr, $what:expr) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let to: fn(&$t) -> [u8; $len] = $to;
+                serialize_bytes(&to(self), serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let from: fn(&[u8; $len]) -> CtOption<$t> = $from;
+                let bytes = deserialize_bytes::<D, $len>(deserializer)?;
+                Option::from(from(&bytes))
+                    .ok_or_else(|| de::Error::custom(concat!("invalid ", $what, " encoding")))
+            }
+        }
+    };
+}
+
+impl_serde!(Fq, 32, Fq::to_bytes, Fq::from_bytes, "Fq");
+impl_serde!(Fq2, 64, Fq2::to_bytes, Fq2::from_bytes, "Fq2");
+impl_serde!(Fr, 32, |s| s.to_repr(), |b| Fr::from_repr(*b), "Fr");
+impl_serde!(
+    G1Affine,
+    32,
+    |p| p.to_bytes().as_ref().try_into().unwrap(),
+    |b| {
+        let mut repr = G1Compressed::default();
+        repr.as_mut().copy_from_slice(b);
+        G1Affine::from_bytes(&repr)
+    },
+    "G1"
+);
+impl_serde!(
+    G2Affine,
+    64,
+    |p| p.to_bytes().as_ref().try_into().unwrap(),
+    |b| {
+        let mut repr = G2Compressed::default();
+        repr.as_mut().copy_from_slice(b);
+        G2Affine::from_bytes(&repr).and_then(|p| {
+            let torsion_free = p.to_curve().is_torsion_free();
+            CtOption::new(p, torsion_free)
+        })
+    },
+    "G2"
+);
+impl_serde!(Gt, 384, Gt::to_bytes, Gt::from_bytes, "Gt");
+
+#[cfg(feature = "alloc")]
+mod prepared {
+    use super::*;
+    use crate::bn256::{G2Prepared, G2_PREPARED_BYTES};
+    use alloc::vec;
+
+    /// `G2Prepared` is serialized as the encoding of [`G2Prepared::to_bytes`],
+    /// as bytes for binary formats and a hex string for human readable ones.
+    ///
+    /// Deserialization has the checks of [`G2Prepared::from_bytes`], so the
+    /// point is in G2 and the coefficients are those of the point.
+    impl Serialize for G2Prepared {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let bytes = self.to_bytes();
+            if serializer.is_human_readable() {
+                serializer.collect_str(&Hex(&bytes))
+            } else {
+                serializer.serialize_bytes(&bytes)
+            }
+        }
+    }
+
+    struct PreparedVisitor;
+
+    impl PreparedVisitor {
+        fn decode<E: de::Error>(&self, bytes: &[u8]) -> Result<G2Prepared, E> {
+            G2Prepared::from_bytes(bytes).ok_or_else(|| E::custom("invalid G2Prepared encoding"))
+        }
+    }
+
+    impl<'de> Visitor<'de> for PreparedVisitor {
+        type Value = G2Prepared;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "an encoded G2Prepared of at most {} bytes",
+                G2_PREPARED_BYTES
+            )
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if v.len() > 2 * G2_PREPARED_BYTES {
+                return Err(E::invalid_length(v.len(), &self));
+            }
+            let mut bytes = vec![0u8; v.len() / 2];
+            hex::decode_to_slice(v, &mut bytes)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))?;
+            self.decode(&bytes)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            self.decode(v)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = vec![];
+            while let Some(byte) = seq.next_element()? {
+                if bytes.len() == G2_PREPARED_BYTES {
+                    return Err(de::Error::invalid_length(bytes.len() + 1, &self));
+                }
+                bytes.push(byte);
+            }
+            self.decode(&bytes)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for G2Prepared {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(PreparedVisitor)
+            } else {
+                deserializer.deserialize_bytes(PreparedVisitor)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn256::fq12::Fq12;
+    use crate::bn256::{pairing, G2Prepared, G1, G2};
+    use ff::Field;
+    use group::{Curve, Group};
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    fn roundtrip<T>(value: &T, json_len: usize, bin_len: usize)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+    {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(json.len(), json_len);
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+
+        let bin = bincode::serialize(value).unwrap();
+        assert_eq!(bin.len(), bin_len);
+        assert_eq!(&bincode::deserialize::<T>(&bin).unwrap(), value);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+
+        for _ in 0..10 {
+            roundtrip(&Fq::random(&mut rng), 2 + 64, 32);
+            roundtrip(&Fq2::random(&mut rng), 2 + 128, 64);
+            roundtrip(&Fr::random(&mut rng), 2 + 64, 32);
+
+            let s = Fr::random(&mut rng);
+            let g1 = (G1::generator() * s).to_affine();
+            let g2 = (G2::generator() * s).to_affine();
+            roundtrip(&g1, 2 + 64, 32);
+            roundtrip(&g2, 2 + 128, 64);
+            roundtrip(&pairing(&g1, &g2), 2 + 768, 384);
+        }
+        roundtrip(&G1Affine::identity(), 2 + 64, 32);
+        roundtrip(&G2Affine::identity(), 2 + 128, 64);
+        roundtrip(&Gt::identity(), 2 + 768, 384);
+
+        assert_eq!(
+            serde_json::to_string(&Fq::one()).unwrap(),
+            "\"0100000000000000000000000000000000000000000000000000000000000000\""
+        );
+
+        for p in [G2Affine::generator(), G2Affine::identity()] {
+            let prepared = G2Prepared::from_affine(p);
+            let json = serde_json::to_string(&prepared).unwrap();
+            assert_eq!(json.len(), 2 + 2 * prepared.to_bytes().len());
+            let back: G2Prepared = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.coeffs, prepared.coeffs);
+            assert_eq!(back.infinity, prepared.infinity);
+            assert_eq!(back.point, prepared.point);
+
+            let bin = bincode::serialize(&prepared).unwrap();
+            assert_eq!(bin.len(), 8 + 160 + prepared.coeffs.len() * 3 * 64);
+            let back: G2Prepared = bincode::deserialize(&bin).unwrap();
+            assert_eq!(back.coeffs, prepared.coeffs);
+            assert_eq!(back.infinity, prepared.infinity);
+            assert_eq!(back.point, prepared.point);
+        }
+    }
+
+    #[test]
+    fn test_serde_rejects_invalid() {
+        // The moduli are not canonical encodings.
+        let q = "\"47fd7cd8168c203c8dca7168916a81975d588181b64550b829a031e1724e6430\"";
+        assert!(serde_json::from_str::<Fq>(q).is_err());
+        let r = "\"010000f093f5e1439170b97948e833285d588181b64550b829a031e1724e6430\"";
+        assert!(serde_json::from_str::<Fr>(r).is_err());
+
+        // Wrong length and non-hex strings.
+        assert!(serde_json::from_str::<Fq>("\"0100\"").is_err());
+        assert!(serde_json::from_str::<Fq>(&q.replace('4', "z")).is_err());
+
+        // An x coordinate for which x^3 + 3 is not a square.
+        let mut x = Fq::one();
+        while bool::from((x.square() * x + Fq::from(3)).sqrt().is_some()) {
+            x += Fq::one();
+        }
+        assert!(bincode::deserialize::<G1Affine>(&x.to_bytes()).is_err());
+
+        // A point on the twist that is not in the order r subgroup.
+        let mut rng = XorShiftRng::from_seed([
+            0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06,
+            0xbc, 0xe5,
+        ]);
+        let p = loop {
+            let p = <G2 as Group>::random(&mut rng);
+            if !bool::from(p.is_torsion_free()) {
+                break p.to_affine();
+            }
+        };
+        let bin = bincode::serialize(&p).unwrap();
+        assert!(bincode::deserialize::<G2Affine>(&bin).is_err());
+
+        // An element of Fq12 outside of Gt.
+        let g = Gt(Fq12::random(&mut rng));
+        let bin = bincode::serialize(&g).unwrap();
+        assert!(bincode::deserialize::<Gt>(&bin).is_err());
+        assert!(bincode::deserialize::<Gt>(&[0u8; 384]).is_err());
+
+        // G2Prepared with a truncated list of coefficients, and with a
+        // corrupted coefficient.
+        let mut prepared = G2Prepared::from_affine(G2Affine::generator());
+        let mut bin = bincode::serialize(&prepared).unwrap();
+        let last = bin.len() - 1;
+        bin[last] ^= 1;
+        assert!(bincode::deserialize::<G2Prepared>(&bin).is_err());
+        prepared.coeffs.pop();
+        let bin = bincode::serialize(&prepared).unwrap();
+        assert!(bincode::deserialize::<G2Prepared>(&bin).is_err());
+        let json = serde_json::to_string(&prepared).unwrap();
+        assert!(serde_json::from_str::<G2Prepared>(&json).is_err());
+
+        // Forged G2Prepared, whose checksums are valid: zeroed coefficients,
+        // and a point outside the subgroup with its own coefficients.
+        let mut zeroed = G2Prepared::from_affine(G2Affine::generator());
+        for c in zeroed.coeffs.iter_mut() {
+            *c = (Fq2::zero(), Fq2::zero(), Fq2::zero());
+        }
+        for forged in [zeroed, G2Prepared::from_affine(p)] {
+            let bin = bincode::serialize(&forged).unwrap();
+            assert!(bincode::deserialize::<G2Prepared>(&bin).is_err());
+            let json = serde_json::to_string(&forged).unwrap();
+            assert!(serde_json::from_str::<G2Prepared>(&json).is_err());
+        }
+    }
+}
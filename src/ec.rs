@@ -3,10 +3,12 @@ macro_rules! new_curve_impl {
     $name:ident,
     $name_affine:ident,
     $name_compressed:ident,
+    $name_uncompressed:ident,
     $base:ident,
     $scalar:ident,
     $generator:expr,
     $constant_b:expr,
+    $endo_zeta:expr,
     $curve_id:literal
     ) => {
 
@@ -27,6 +29,9 @@ macro_rules! new_curve_impl {
         #[derive(Copy, Clone)]
         $($privacy)* struct $name_compressed([u8; $base::size()]);
 
+        #[derive(Copy, Clone)]
+        $($privacy)* struct $name_uncompressed([u8; 2 * $base::size()]);
+
 
         impl $name {
             pub fn generator() -> Self {
@@ -41,6 +46,44 @@ macro_rules! new_curve_impl {
             const fn curve_constant_b() -> $base {
                 $name_affine::curve_constant_b()
             }
+
+            /// Multiplies this point by `by` with a constant-time double-and-add.
+            // G1 multiplies with GLV and only uses this in tests.
+            #[allow(dead_code)]
+            pub(crate) fn mul_double_and_add(&self, by: &$scalar) -> $name {
+                let mut acc = $name::identity();
+
+                // This is a simple double-and-add implementation of point
+                // multiplication, moving from most significant to least
+                // significant bit of the scalar.
+                //
+                // NOTE: We skip the leading bit because it's always unset.
+                for bit in by
+                    .to_repr()
+                    .iter()
+                    .rev()
+                    .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
+                    .skip(1)
+                {
+                    acc = acc.double();
+                    acc = $name::conditional_select(&acc, &(acc + self), bit);
+                }
+
+                acc
+            }
+
+            /// Computes the multi-scalar multiplication of `bases` by `scalars`
+            /// with windowed Pippenger. Panics if the slices differ in length.
+            #[cfg(feature = "alloc")]
+            pub fn msm(bases: &[$name_affine], scalars: &[$scalar]) -> Self {
+                crate::arithmetic::msm(bases, scalars)
+            }
+
+            /// Multi-threaded variant of [`Self::msm`].
+            #[cfg(feature = "multicore")]
+            pub fn msm_parallel(bases: &[$name_affine], scalars: &[$scalar]) -> Self {
+                crate::arithmetic::msm_parallel(bases, scalars)
+            }
         }
 
         impl $name_affine {
@@ -81,8 +124,8 @@ macro_rules! new_curve_impl {
 
         // Compressed
 
-        impl std::fmt::Debug for $name_compressed {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        impl core::fmt::Debug for $name_compressed {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
                 self.0[..].fmt(f)
             }
         }
@@ -105,6 +148,32 @@ macro_rules! new_curve_impl {
             }
         }
 
+        // Uncompressed
+
+        impl core::fmt::Debug for $name_uncompressed {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                self.0[..].fmt(f)
+            }
+        }
+
+        impl Default for $name_uncompressed {
+            fn default() -> Self {
+                $name_uncompressed([0; 2 * $base::size()])
+            }
+        }
+
+        impl AsRef<[u8]> for $name_uncompressed {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl AsMut<[u8]> for $name_uncompressed {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
 
         // Jacobian implementations
 
@@ -175,6 +244,14 @@ macro_rules! new_curve_impl {
 
             const CURVE_ID: &'static str = $curve_id;
 
+            fn endo(&self) -> Self {
+                $name {
+                    x: self.x * $endo_zeta,
+                    y: self.y,
+                    z: self.z,
+                }
+            }
+
             fn jacobian_coordinates(&self) -> ($base, $base, $base) {
                (self.x, self.y, self.z)
             }
@@ -355,8 +432,8 @@ macro_rules! new_curve_impl {
 
         // Affine implementations
 
-        impl std::fmt::Debug for $name_affine {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        impl core::fmt::Debug for $name_affine {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
                 if self.infinity.into() {
                     write!(f, "Infinity")
                 } else {
@@ -457,6 +534,70 @@ macro_rules! new_curve_impl {
             }
         }
 
+        impl group::UncompressedEncoding for $name_affine {
+            type Uncompressed = $name_uncompressed;
+
+            fn from_uncompressed(bytes: &Self::Uncompressed) -> CtOption<Self> {
+                Self::from_uncompressed_unchecked(bytes).and_then(|p| {
+                    CtOption::new(
+                        p,
+                        p.is_on_curve()
+                            & group::cofactor::CofactorGroup::is_torsion_free(&p.to_curve()),
+                    )
+                })
+            }
+
+            fn from_uncompressed_unchecked(bytes: &Self::Uncompressed) -> CtOption<Self> {
+                let bytes = &bytes.0;
+
+                // The two most significant bits of the encoding carry the flags; the
+                // infinity flag is bit 6 and bit 7 is reserved and must be unset.
+                let flags = bytes[2 * $base::size() - 1];
+                let infinity_flag_set = Choice::from((flags >> 6) & 1);
+                let reserved_flag_unset = !Choice::from(flags >> 7);
+
+                let mut xbytes = [0u8; $base::size()];
+                xbytes.copy_from_slice(&bytes[..$base::size()]);
+                let mut ybytes = [0u8; $base::size()];
+                ybytes.copy_from_slice(&bytes[$base::size()..]);
+                ybytes[$base::size() - 1] &= 0b0011_1111;
+
+                $base::from_bytes(&xbytes).and_then(|x| {
+                    $base::from_bytes(&ybytes).and_then(|y| {
+                        let p = $name_affine::conditional_select(
+                            &$name_affine {
+                                x,
+                                y,
+                                infinity: Choice::from(0u8),
+                            },
+                            &$name_affine::identity(),
+                            infinity_flag_set,
+                        );
+
+                        CtOption::new(
+                            p,
+                            // If the infinity flag is set, both coordinates must be zero.
+                            reserved_flag_unset
+                                & ((!infinity_flag_set) | (x.is_zero() & y.is_zero())),
+                        )
+                    })
+                })
+            }
+
+            fn to_uncompressed(&self) -> Self::Uncompressed {
+                let x = $base::conditional_select(&self.x, &$base::zero(), self.infinity);
+                let y = $base::conditional_select(&self.y, &$base::zero(), self.infinity);
+
+                let mut res = [0u8; 2 * $base::size()];
+                res[..$base::size()].copy_from_slice(&x.to_bytes()[..]);
+                res[$base::size()..].copy_from_slice(&y.to_bytes()[..]);
+                res[2 * $base::size() - 1] |=
+                    u8::conditional_select(&0u8, &(1u8 << 6), self.infinity);
+
+                $name_uncompressed(res)
+            }
+        }
+
         impl group::prime::PrimeCurveAffine for $name_affine {
             type Curve = $name;
             type Scalar = $scalar;
@@ -677,32 +818,11 @@ macro_rules! new_curve_impl {
             }
         }
 
-        #[allow(clippy::suspicious_arithmetic_impl)]
         impl<'a, 'b> Mul<&'b $scalar> for &'a $name {
             type Output = $name;
 
             fn mul(self, other: &'b $scalar) -> Self::Output {
-                // TODO: make this faster
-
-                let mut acc = $name::identity();
-
-                // This is a simple double-and-add implementation of point
-                // multiplication, moving from most significant to least
-                // significant bit of the scalar.
-                //
-                // NOTE: We skip the leading bit because it's always unset.
-                for bit in other
-                    .to_repr()
-                    .iter()
-                    .rev()
-                    .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-                    .skip(1)
-                {
-                    acc = acc.double();
-                    acc = $name::conditional_select(&acc, &(acc + self), bit);
-                }
-
-                acc
+                self.mul_scalar(other)
             }
         }
 
@@ -788,32 +908,11 @@ macro_rules! new_curve_impl {
             }
         }
 
-        #[allow(clippy::suspicious_arithmetic_impl)]
         impl<'a, 'b> Mul<&'b $scalar> for &'a $name_affine {
             type Output = $name;
 
             fn mul(self, other: &'b $scalar) -> Self::Output {
-                // TODO: make this faster
-
-                let mut acc = $name::identity();
-
-                // This is a simple double-and-add implementation of point
-                // multiplication, moving from most significant to least
-                // significant bit of the scalar.
-                //
-                // NOTE: We skip the leading bit because it's always unset.
-                for bit in other
-                    .to_repr()
-                    .iter()
-                    .rev()
-                    .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
-                    .skip(1)
-                {
-                    acc = acc.double();
-                    acc = $name::conditional_select(&acc, &(acc + self), bit);
-                }
-
-                acc
+                self.to_curve().mul_scalar(other)
             }
         }
     };
@@ -1,5 +1,7 @@
-#![feature(asm)]
-#![feature(asm_const)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 #[macro_use]
 mod ec;
@@ -0,0 +1,378 @@
+//! A toy BN curve with $u = -4619$, for testing the generic pairing in
+//! [`crate::arithmetic`] on a negative $u$ and an M-type twist, which BN254
+//! does not have. The base field is small enough for `u128` arithmetic, so
+//! none of this is constant time.
+//!
+//! The curve is $E: y^2 = x^3 + 5$ over $\mathbb{F}_q$ with
+//! $q = 16383260232357583$, which has prime order
+//! $r = 16383260104346617$. The tower is $\mathbb{F}_{q^2} = \mathbb{F}_q(i)$
+//! with $i^2 = -1$, $v^3 = \xi = 5 + i$ and $w^2 = v$, and G2 lives on the
+//! M-type twist $E': y^2 = x^3 + 5 \xi$.
+
+use crate::arithmetic::{BnAffine, BnFq12, BnFq2, BnParameters, TwistType};
+use core::ops::{Add, Mul, Neg, Sub};
+use ff::Field;
+use rand::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+pub const Q: u64 = 16383260232357583;
+pub const R: u64 = 16383260104346617;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fp(pub u64);
+
+/// $c_0 + c_1 i$
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fp2(pub Fp, pub Fp);
+
+/// $c_0 + c_1 v + c_2 v^2$
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fp6(pub Fp2, pub Fp2, pub Fp2);
+
+/// $c_0 + c_1 w$
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fp12(pub Fp6, pub Fp6);
+
+/// The non-residue $\xi = 5 + i$.
+pub const XI: Fp2 = Fp2(Fp(5), Fp(1));
+
+impl Fp {
+    fn add(&self, rhs: &Fp) -> Fp {
+        Fp((self.0 + rhs.0) % Q)
+    }
+
+    fn neg(&self) -> Fp {
+        Fp((Q - self.0) % Q)
+    }
+
+    fn mul(&self, rhs: &Fp) -> Fp {
+        Fp((self.0 as u128 * rhs.0 as u128 % Q as u128) as u64)
+    }
+
+    fn invert(&self) -> CtOption<Fp> {
+        CtOption::new(self.pow_vartime([Q - 2]), !self.is_zero())
+    }
+}
+
+impl Fp2 {
+    fn add(&self, rhs: &Fp2) -> Fp2 {
+        Fp2(self.0 + rhs.0, self.1 + rhs.1)
+    }
+
+    fn neg(&self) -> Fp2 {
+        Fp2(-self.0, -self.1)
+    }
+
+    fn mul(&self, rhs: &Fp2) -> Fp2 {
+        Fp2(
+            self.0 * rhs.0 - self.1 * rhs.1,
+            self.0 * rhs.1 + self.1 * rhs.0,
+        )
+    }
+
+    fn invert(&self) -> CtOption<Fp2> {
+        (self.0.square() + self.1.square())
+            .invert()
+            .map(|t| Fp2(self.0 * t, -self.1 * t))
+    }
+}
+
+impl Fp6 {
+    fn add(&self, rhs: &Fp6) -> Fp6 {
+        Fp6(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+
+    fn neg(&self) -> Fp6 {
+        Fp6(-self.0, -self.1, -self.2)
+    }
+
+    fn mul(&self, rhs: &Fp6) -> Fp6 {
+        Fp6(
+            self.0 * rhs.0 + (self.1 * rhs.2 + self.2 * rhs.1) * XI,
+            self.0 * rhs.1 + self.1 * rhs.0 + self.2 * rhs.2 * XI,
+            self.0 * rhs.2 + self.1 * rhs.1 + self.2 * rhs.0,
+        )
+    }
+
+    fn mul_by_v(&self) -> Fp6 {
+        Fp6(self.2 * XI, self.0, self.1)
+    }
+
+    fn invert(&self) -> CtOption<Fp6> {
+        let t0 = self.0.square() - self.1 * self.2 * XI;
+        let t1 = self.2.square() * XI - self.0 * self.1;
+        let t2 = self.1.square() - self.0 * self.2;
+        let norm = self.0 * t0 + (self.2 * t1 + self.1 * t2) * XI;
+        norm.invert().map(|t| Fp6(t0 * t, t1 * t, t2 * t))
+    }
+}
+
+impl Fp12 {
+    fn add(&self, rhs: &Fp12) -> Fp12 {
+        Fp12(self.0 + rhs.0, self.1 + rhs.1)
+    }
+
+    fn neg(&self) -> Fp12 {
+        Fp12(-self.0, -self.1)
+    }
+
+    fn mul(&self, rhs: &Fp12) -> Fp12 {
+        Fp12(
+            self.0 * rhs.0 + (self.1 * rhs.1).mul_by_v(),
+            self.0 * rhs.1 + self.1 * rhs.0,
+        )
+    }
+
+    fn invert(&self) -> CtOption<Fp12> {
+        (self.0.square() - self.1.square().mul_by_v())
+            .invert()
+            .map(|t| Fp12(self.0 * t, -self.1 * t))
+    }
+}
+
+macro_rules! toy_field {
+    ($field:ident, $random:expr, $one:expr, ($($c:tt),*)) => {
+        impl ConstantTimeEq for $field {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                $(self.$c.ct_eq(&other.$c))&*
+            }
+        }
+
+        impl ConditionallySelectable for $field {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                $field($(ConditionallySelectable::conditional_select(&a.$c, &b.$c, choice)),*)
+            }
+        }
+
+        impl<'a> Neg for &'a $field {
+            type Output = $field;
+
+            fn neg(self) -> $field {
+                $field::neg(self)
+            }
+        }
+
+        impl Neg for $field {
+            type Output = $field;
+
+            fn neg(self) -> $field {
+                -&self
+            }
+        }
+
+        impl<'a, 'b> Add<&'b $field> for &'a $field {
+            type Output = $field;
+
+            fn add(self, rhs: &'b $field) -> $field {
+                $field::add(self, rhs)
+            }
+        }
+
+        impl<'a, 'b> Sub<&'b $field> for &'a $field {
+            type Output = $field;
+
+            fn sub(self, rhs: &'b $field) -> $field {
+                $field::add(self, &-rhs)
+            }
+        }
+
+        impl<'a, 'b> Mul<&'b $field> for &'a $field {
+            type Output = $field;
+
+            fn mul(self, rhs: &'b $field) -> $field {
+                $field::mul(self, rhs)
+            }
+        }
+
+        impl_binops_additive!($field, $field);
+        impl_binops_multiplicative!($field, $field);
+
+        impl Field for $field {
+            fn random(mut rng: impl RngCore) -> Self {
+                $random(&mut rng)
+            }
+
+            fn zero() -> Self {
+                Self::default()
+            }
+
+            fn one() -> Self {
+                $one
+            }
+
+            fn square(&self) -> Self {
+                self * self
+            }
+
+            fn double(&self) -> Self {
+                self + self
+            }
+
+            fn invert(&self) -> CtOption<Self> {
+                $field::invert(self)
+            }
+
+            fn sqrt(&self) -> CtOption<Self> {
+                unimplemented!("the pairing takes no square roots")
+            }
+        }
+    };
+}
+
+toy_field!(
+    Fp,
+    |rng: &mut dyn RngCore| Fp(rng.next_u64() % Q),
+    Fp(1),
+    (0)
+);
+toy_field!(
+    Fp2,
+    |rng: &mut dyn RngCore| Fp2(Fp::random(&mut *rng), Fp::random(rng)),
+    Fp2(Fp::one(), Fp::zero()),
+    (0, 1)
+);
+toy_field!(
+    Fp6,
+    |rng: &mut dyn RngCore| Fp6(
+        Fp2::random(&mut *rng),
+        Fp2::random(&mut *rng),
+        Fp2::random(rng)
+    ),
+    Fp6(Fp2::one(), Fp2::zero(), Fp2::zero()),
+    (0, 1, 2)
+);
+toy_field!(
+    Fp12,
+    |rng: &mut dyn RngCore| Fp12(Fp6::random(&mut *rng), Fp6::random(rng)),
+    Fp12(Fp6::one(), Fp6::zero()),
+    (0, 1)
+);
+
+impl BnFq2<Fp> for Fp2 {
+    fn conjugate(&mut self) {
+        self.1 = -self.1;
+    }
+
+    fn mul_by_base(&mut self, c: &Fp) {
+        self.0 *= c;
+        self.1 *= c;
+    }
+}
+
+// The sparse products are full products here, so that they check where the
+// generic code puts the line coefficients rather than how BN254 multiplies.
+impl BnFq12<Fp2> for Fp12 {
+    fn mul_by_034(&mut self, c0: &Fp2, c3: &Fp2, c4: &Fp2) {
+        let zero = Fp2::zero();
+        *self *= Fp12(Fp6(*c0, zero, zero), Fp6(*c3, *c4, zero));
+    }
+
+    fn mul_by_014(&mut self, c0: &Fp2, c1: &Fp2, c4: &Fp2) {
+        let zero = Fp2::zero();
+        *self *= Fp12(Fp6(*c0, *c1, zero), Fp6(zero, *c4, zero));
+    }
+
+    fn conjugate(&mut self) {
+        self.1 = -self.1;
+    }
+
+    fn frobenius_map(&mut self, power: usize) {
+        for _ in 0..power {
+            *self = self.pow_vartime([Q]);
+        }
+    }
+
+    fn cyclotomic_square(&mut self) {
+        *self = self.square();
+    }
+
+    fn cyclotomic_exp_vartime(&self, exp: &[u64]) -> Fp12 {
+        self.pow_vartime(exp)
+    }
+}
+
+/// An affine point of $E$ or $E'$, `None` being the identity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point<F>(pub Option<(F, F)>);
+
+impl<F: Field> BnAffine<F> for Point<F> {
+    fn xy(&self) -> Option<(F, F)> {
+        self.0
+    }
+}
+
+impl<F: Field> Point<F> {
+    pub fn is_on_curve(&self, b: &F) -> bool {
+        self.0.is_none_or(|(x, y)| y.square() == x.square() * x + b)
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        let ((x1, y1), (x2, y2)) = match (self.0, rhs.0) {
+            (None, _) => return *rhs,
+            (_, None) => return *self,
+            (Some(p), Some(q)) => (p, q),
+        };
+        let lambda = if x1 != x2 {
+            (y2 - y1) * (x2 - x1).invert().unwrap()
+        } else if y1 == y2 && !bool::from(y1.is_zero()) {
+            let x_squared = x1.square();
+            (x_squared.double() + x_squared) * y1.double().invert().unwrap()
+        } else {
+            return Point(None);
+        };
+        let x3 = lambda.square() - x1 - x2;
+        Point(Some((x3, lambda * (x1 - x3) - y1)))
+    }
+
+    pub fn mul(&self, by: u64) -> Self {
+        let mut acc = Point(None);
+        for i in (0..64).rev() {
+            acc = acc.add(&acc);
+            if (by >> i) & 1 == 1 {
+                acc = acc.add(self);
+            }
+        }
+        acc
+    }
+}
+
+/// The coefficient $b = 5$ of $E$.
+pub const B: Fp = Fp(5);
+
+/// A generator of $E(\mathbb{F}_q)$.
+pub const G1_GENERATOR: Point<Fp> = Point(Some((Fp(10649273608710449), Fp(10300379318340987))));
+
+/// A generator of the order $r$ subgroup of $E'(\mathbb{F}_{q^2})$.
+pub const G2_GENERATOR: Point<Fp2> = Point(Some((
+    Fp2(Fp(7588536044522338), Fp(14355986830758865)),
+    Fp2(Fp(1538744772223637), Fp(14163117667061158)),
+)));
+
+#[derive(Clone, Debug)]
+pub struct ToyBn;
+
+impl BnParameters for ToyBn {
+    type Fq = Fp;
+    type Fq2 = Fp2;
+    type Fq12 = Fp12;
+    type G1Affine = Point<Fp>;
+    type G2Affine = Point<Fp2>;
+
+    const X: &'static [u64] = &[4619];
+
+    const X_IS_NEGATIVE: bool = true;
+
+    // NAF of 27712 = |6u + 2|
+    const ATE_LOOP_COUNT: &'static [i8] = &[0, 0, 0, 0, 0, 0, 1, 0, 0, 0, -1, 0, -1, 0, 0, 1];
+
+    const TWIST_TYPE: TwistType = TwistType::M;
+
+    // xi^-((q - 1) / 3)
+    const TWIST_MUL_BY_Q_X: Fp2 = Fp2(Fp(6533406202216795), Fp(10779964656344034));
+
+    // xi^-((q - 1) / 2)
+    const TWIST_MUL_BY_Q_Y: Fp2 = Fp2(Fp(9575302046510923), Fp(1273270464518134));
+
+    // xi^-((q^2 - 1) / 3)
+    const TWIST_MUL_BY_Q2_X: Fp2 = Fp2(Fp(1773463964533), Fp(0));
+}
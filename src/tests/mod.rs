@@ -1 +1,2 @@
+pub mod bn;
 pub mod field;